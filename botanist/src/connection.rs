@@ -0,0 +1,62 @@
+use juniper::{FieldError, GraphQLObject, Value};
+use std::fmt::Display;
+use std::str::FromStr;
+
+const CURSOR_PREFIX: &str = "cursor:";
+
+#[derive(GraphQLObject, Debug, Clone)]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub has_previous_page: bool,
+    pub start_cursor: Option<String>,
+    pub end_cursor: Option<String>,
+}
+
+// Cursors are opaque to clients, under the hood they're just hex encoded primary keys
+pub fn encode_cursor<T: Display>(id: &T) -> String {
+    format!("{}{}", CURSOR_PREFIX, id)
+        .bytes()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn decode_cursor<T: FromStr>(cursor: &str) -> Result<T, FieldError> {
+    let invalid_cursor = || FieldError::new(format!("Invalid cursor '{}'", cursor), Value::null());
+
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| {
+            cursor
+                .get(i..i + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid_cursor)?;
+
+    String::from_utf8(bytes)
+        .ok()
+        .and_then(|decoded| {
+            decoded
+                .strip_prefix(CURSOR_PREFIX)
+                .and_then(|id| id.parse::<T>().ok())
+        })
+        .ok_or_else(invalid_cursor)
+}
+
+// Composite primary keys are encoded one column at a time, hex never contains the separator
+pub fn join_cursor(parts: &[String]) -> String {
+    parts.join(".")
+}
+
+pub fn split_cursor(cursor: &str, parts: usize) -> Result<Vec<&str>, FieldError> {
+    let split = cursor.split('.').collect::<Vec<&str>>();
+
+    if split.len() == parts {
+        Ok(split)
+    } else {
+        Err(FieldError::new(
+            format!("Invalid cursor '{}'", cursor),
+            Value::null(),
+        ))
+    }
+}
@@ -63,3 +63,285 @@ pub mod prefix_search {
 
 #[cfg(not(feature = "postgres_prefix_search"))]
 pub mod prefix_search {}
+
+pub mod partition {
+    use diesel::backend::Backend;
+    use diesel::expression::{AppearsOnTable, Expression, NonAggregate, SelectableExpression};
+    use diesel::query_builder::{AstPass, Query, QueryFragment, QueryId};
+    use diesel::query_dsl::RunQueryDsl;
+    use diesel::query_source::QuerySource;
+    use diesel::result::QueryResult;
+    use diesel::serialize::ToSql;
+    use diesel::sql_types::{BigInt, HasSqlType};
+
+    // Applies a limit and offset to every partition of a query instead of the query as a whole, i.e:
+    // SELECT <select> FROM (
+    //     SELECT <table>.*, ROW_NUMBER() OVER (PARTITION BY <partition_by> ORDER BY <order_by, ...>) AS __botanist_row
+    //     FROM (<query>) AS <table>
+    // ) AS <table> WHERE __botanist_row > <offset> AND __botanist_row <= <offset + limit>
+    //
+    // Aliasing each subquery as the original table keeps qualified column references valid
+    #[derive(Debug, Clone)]
+    pub struct PartitionedLimit<Q, T, S, P, O> {
+        query: Q,
+        table: T,
+        select: S,
+        partition_by: P,
+        order_by: Vec<O>,
+        limit: i64,
+        offset: i64,
+    }
+
+    pub fn partitioned_limit<Q, T, S, P, O>(
+        query: Q,
+        table: T,
+        select: S,
+        partition_by: P,
+        order_by: Vec<O>,
+        limit: i64,
+        offset: i64,
+    ) -> PartitionedLimit<Q, T, S, P, O> {
+        PartitionedLimit {
+            query,
+            table,
+            select,
+            partition_by,
+            order_by,
+            limit,
+            offset,
+        }
+    }
+
+    impl<Q: Query, T, S, P, O> Query for PartitionedLimit<Q, T, S, P, O> {
+        type SqlType = Q::SqlType;
+    }
+
+    impl<Q, T, S, P, O> QueryId for PartitionedLimit<Q, T, S, P, O> {
+        type QueryId = ();
+
+        const HAS_STATIC_QUERY_ID: bool = false;
+    }
+
+    impl<Conn, Q, T, S, P, O> RunQueryDsl<Conn> for PartitionedLimit<Q, T, S, P, O> {}
+
+    impl<DB, Q, T, S, P, O> QueryFragment<DB> for PartitionedLimit<Q, T, S, P, O>
+    where
+        DB: Backend + HasSqlType<BigInt>,
+        i64: ToSql<BigInt, DB>,
+        Q: QueryFragment<DB>,
+        T: QuerySource,
+        T::FromClause: QueryFragment<DB>,
+        S: QueryFragment<DB>,
+        P: QueryFragment<DB>,
+        O: QueryFragment<DB>,
+    {
+        fn walk_ast(&self, mut out: AstPass<DB>) -> QueryResult<()> {
+            let table = self.table.from_clause();
+
+            out.push_sql("SELECT ");
+            self.select.walk_ast(out.reborrow())?;
+            out.push_sql(" FROM (SELECT ");
+            table.walk_ast(out.reborrow())?;
+            out.push_sql(".*, ROW_NUMBER() OVER (PARTITION BY ");
+            self.partition_by.walk_ast(out.reborrow())?;
+            out.push_sql(" ORDER BY ");
+            for (i, order_by) in self.order_by.iter().enumerate() {
+                if i > 0 {
+                    out.push_sql(", ");
+                }
+
+                order_by.walk_ast(out.reborrow())?;
+            }
+            out.push_sql(") AS __botanist_row FROM (");
+            self.query.walk_ast(out.reborrow())?;
+            out.push_sql(") AS ");
+            table.walk_ast(out.reborrow())?;
+            out.push_sql(") AS ");
+            table.walk_ast(out.reborrow())?;
+            out.push_sql(" WHERE __botanist_row > ");
+            out.push_bind_param::<BigInt, _>(&self.offset)?;
+            out.push_sql(" AND __botanist_row <= ");
+            out.push_bind_param::<BigInt, _>(&(self.offset + self.limit))?;
+            out.push_sql(" ORDER BY ");
+            self.partition_by.walk_ast(out.reborrow())?;
+            out.push_sql(", __botanist_row");
+            Ok(())
+        }
+    }
+
+    // Numbers the rows of each partition as __botanist_row, for queries that can't be aliased as a single table (i.e a
+    // join). Selected alongside the rows it numbers, then limited with `numbered_limit`
+    #[derive(Debug, Clone)]
+    pub struct RowNumber<P, O> {
+        partition_by: P,
+        order_by: O,
+    }
+
+    pub fn row_number<P: Expression, O: Expression>(partition_by: P, order_by: O) -> RowNumber<P, O> {
+        RowNumber { partition_by, order_by }
+    }
+
+    impl<P, O> QueryId for RowNumber<P, O> {
+        type QueryId = ();
+
+        const HAS_STATIC_QUERY_ID: bool = false;
+    }
+
+    impl<P, O> Expression for RowNumber<P, O> {
+        type SqlType = BigInt;
+    }
+
+    impl<P, O> NonAggregate for RowNumber<P, O> {}
+
+    impl<QS, P: AppearsOnTable<QS>, O: AppearsOnTable<QS>> AppearsOnTable<QS> for RowNumber<P, O> {}
+
+    impl<QS, P: SelectableExpression<QS>, O: SelectableExpression<QS>> SelectableExpression<QS> for RowNumber<P, O> {}
+
+    impl<DB: Backend, P: QueryFragment<DB>, O: QueryFragment<DB>> QueryFragment<DB> for RowNumber<P, O> {
+        fn walk_ast(&self, mut out: AstPass<DB>) -> QueryResult<()> {
+            out.push_sql("ROW_NUMBER() OVER (PARTITION BY ");
+            self.partition_by.walk_ast(out.reborrow())?;
+            out.push_sql(" ORDER BY ");
+            self.order_by.walk_ast(out.reborrow())?;
+            out.push_sql(") AS __botanist_row");
+            Ok(())
+        }
+    }
+
+    // Limits a query selecting a `row_number` to the rows numbered within the offset and limit, i.e:
+    // SELECT * FROM (<query>) AS __botanist_rows WHERE __botanist_row > <offset> AND __botanist_row <= <offset + limit>
+    #[derive(Debug, Clone)]
+    pub struct NumberedLimit<Q> {
+        query: Q,
+        limit: i64,
+        offset: i64,
+    }
+
+    pub fn numbered_limit<Q>(query: Q, limit: i64, offset: i64) -> NumberedLimit<Q> {
+        NumberedLimit { query, limit, offset }
+    }
+
+    impl<Q: Query> Query for NumberedLimit<Q> {
+        type SqlType = Q::SqlType;
+    }
+
+    impl<Q> QueryId for NumberedLimit<Q> {
+        type QueryId = ();
+
+        const HAS_STATIC_QUERY_ID: bool = false;
+    }
+
+    impl<Conn, Q> RunQueryDsl<Conn> for NumberedLimit<Q> {}
+
+    impl<DB, Q> QueryFragment<DB> for NumberedLimit<Q>
+    where
+        DB: Backend + HasSqlType<BigInt>,
+        i64: ToSql<BigInt, DB>,
+        Q: QueryFragment<DB>,
+    {
+        fn walk_ast(&self, mut out: AstPass<DB>) -> QueryResult<()> {
+            out.push_sql("SELECT * FROM (");
+            self.query.walk_ast(out.reborrow())?;
+            out.push_sql(") AS __botanist_rows WHERE __botanist_row > ");
+            out.push_bind_param::<BigInt, _>(&self.offset)?;
+            out.push_sql(" AND __botanist_row <= ");
+            out.push_bind_param::<BigInt, _>(&(self.offset + self.limit))?;
+            out.push_sql(" ORDER BY __botanist_row");
+            Ok(())
+        }
+    }
+}
+
+pub mod filter {
+    use diesel::backend::Backend;
+    use diesel::expression::{AppearsOnTable, Expression, NonAggregate, SelectableExpression};
+    use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+    use diesel::result::QueryResult;
+    use diesel::sql_types::Bool;
+
+    // Unlike NOT, IS NOT TRUE also holds when the expression is NULL, so a model a filter can't be evaluated for
+    // counts as not matching it
+    #[derive(Debug, Clone)]
+    pub struct IsNotTrue<E> {
+        expr: E,
+    }
+
+    impl<E> QueryId for IsNotTrue<E> {
+        type QueryId = ();
+
+        const HAS_STATIC_QUERY_ID: bool = false;
+    }
+
+    pub fn is_not_true<E: Expression<SqlType = Bool>>(expr: E) -> IsNotTrue<E> {
+        IsNotTrue { expr }
+    }
+
+    impl<E: Expression> Expression for IsNotTrue<E> {
+        type SqlType = Bool;
+    }
+
+    impl<E: NonAggregate> NonAggregate for IsNotTrue<E> {}
+
+    impl<QS, E: AppearsOnTable<QS>> AppearsOnTable<QS> for IsNotTrue<E> {}
+
+    impl<QS, E: SelectableExpression<QS>> SelectableExpression<QS> for IsNotTrue<E> {}
+
+    impl<DB: Backend, E: QueryFragment<DB>> QueryFragment<DB> for IsNotTrue<E> {
+        fn walk_ast(&self, mut out: AstPass<DB>) -> QueryResult<()> {
+            out.push_sql("(");
+            self.expr.walk_ast(out.reborrow())?;
+            out.push_sql(") IS NOT TRUE");
+            Ok(())
+        }
+    }
+}
+
+pub mod aggregate {
+    use diesel::backend::Backend;
+    use diesel::expression::{AppearsOnTable, Expression, SelectableExpression};
+    use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+    use diesel::result::QueryResult;
+    use diesel::sql_types::{Double, Nullable};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum AggregateFunction {
+        Avg,
+        Sum,
+        Min,
+        Max,
+    }
+
+    // Aggregates are exposed as floats; integer sums and averages would otherwise come back as BigInt or Numeric,
+    // neither of which GraphQL has
+    #[derive(Debug, Clone)]
+    pub struct AsDouble<E> {
+        expr: E,
+    }
+
+    impl<E> QueryId for AsDouble<E> {
+        type QueryId = ();
+
+        const HAS_STATIC_QUERY_ID: bool = false;
+    }
+
+    pub fn as_double<E: Expression>(expr: E) -> AsDouble<E> {
+        AsDouble { expr }
+    }
+
+    impl<E: Expression> Expression for AsDouble<E> {
+        type SqlType = Nullable<Double>;
+    }
+
+    impl<QS, E: AppearsOnTable<QS>> AppearsOnTable<QS> for AsDouble<E> {}
+
+    impl<QS, E: SelectableExpression<QS>> SelectableExpression<QS> for AsDouble<E> {}
+
+    impl<DB: Backend, E: QueryFragment<DB>> QueryFragment<DB> for AsDouble<E> {
+        fn walk_ast(&self, mut out: AstPass<DB>) -> QueryResult<()> {
+            out.push_sql("CAST(");
+            self.expr.walk_ast(out.reborrow())?;
+            out.push_sql(" AS DOUBLE PRECISION)");
+            Ok(())
+        }
+    }
+}
@@ -1,42 +1,167 @@
 #![allow(non_camel_case_types)]
 
 use crate::Context as BotanistContext;
-use diesel::result::Error;
 use juniper::Context as JuniperContext;
 use juniper::{DefaultScalarValue, Executor, FieldError, FieldResult, LookAheadSelection};
 use std::collections::HashMap;
 
 pub trait __internal__Preloadable<C: JuniperContext + BotanistContext, T> {
     fn preload_children(
-        self_models: &Vec<T>,
+        self_models: &[T],
         context: &C,
         look_ahead: &LookAheadSelection<DefaultScalarValue>,
-    ) -> Result<(), Error>;
+    ) -> FieldResult<()>;
+}
+
+pub trait __internal__Loadable<C: JuniperContext + BotanistContext, T, Q> {
+    fn load_by_ids(context: &C, ids: &[T]) -> FieldResult<Vec<Q>>;
+
+    fn primary_key(&self) -> T;
+}
+
+pub trait __internal__Keyable {
+    type Key;
+}
+
+// The table a model is loaded from, so other models can join it (i.e through a HasManyThrough's join table)
+pub trait __internal__Table {
+    type Table;
+
+    fn table() -> Self::Table;
+}
+
+pub trait __internal__Filterable {
+    type Filter;
+}
+
+pub trait __internal__Orderable {
+    type OrderBy;
 }
 
 pub trait __internal__CreateMutation<C: JuniperContext + BotanistContext, T, Q> {
-    fn create(context: &C, self_model: T) -> FieldResult<Q>;
+    fn create(
+        context: &C,
+        executor: &Executor<C, DefaultScalarValue>,
+        self_model: T,
+    ) -> FieldResult<Q>;
+
+    fn create_all(
+        context: &C,
+        executor: &Executor<C, DefaultScalarValue>,
+        self_models: Vec<T>,
+    ) -> FieldResult<Vec<Q>>;
+}
+
+// Upserts (with the postgres_upsert feature), only models with a create input have them
+pub trait __internal__Upsert<C: JuniperContext + BotanistContext>: Sized {
+    type Input;
+    type Column;
+
+    fn upsert(
+        context: &C,
+        executor: &Executor<C, DefaultScalarValue>,
+        input: Self::Input,
+        conflict_on: Vec<Self::Column>,
+    ) -> FieldResult<Self>;
+}
+
+// Nested writes. Models referenced through a HasOne are connected or created before the model referencing them
+pub trait __internal__ConnectOrCreate<C: JuniperContext + BotanistContext> {
+    type Input;
+    type Key;
+
+    fn connect_or_create(
+        input: Self::Input,
+        context: &C,
+        connection: &C::Connection,
+    ) -> FieldResult<Self::Key>;
+}
+
+// Children of a HasMany are created after their parent, `F` being the child's forign key column
+pub trait __internal__CreateWithout<F, C: JuniperContext + BotanistContext> {
+    type Input;
+    type Value;
+
+    fn create_without(
+        input: Self::Input,
+        value: Self::Value,
+        context: &C,
+        connection: &C::Connection,
+    ) -> FieldResult<()>;
+}
+
+// Relationship mutations, `F` being the forign key column of the model being (dis)connected. Disconnecting nulls the
+// key if it's still `value`, or regardless of its value if there's none
+pub trait __internal__Reconnect<F, C: JuniperContext + BotanistContext> {
+    type Key;
+    type Value;
+
+    fn connect(
+        key: Self::Key,
+        value: Self::Value,
+        context: &C,
+        connection: &C::Connection,
+    ) -> FieldResult<()>;
+
+    fn disconnect(
+        key: Self::Key,
+        value: Option<Self::Value>,
+        context: &C,
+        connection: &C::Connection,
+    ) -> FieldResult<()>;
 }
 
 pub trait __internal__UpdateMutation<C: JuniperContext + BotanistContext, T, Q> {
-    fn update(context: &C, self_model: T) -> FieldResult<Q>;
+    fn update(
+        context: &C,
+        executor: &Executor<C, DefaultScalarValue>,
+        self_model: T,
+    ) -> FieldResult<Q>;
+
+    fn update_all(
+        context: &C,
+        executor: &Executor<C, DefaultScalarValue>,
+        self_models: Vec<T>,
+    ) -> FieldResult<Vec<Q>>;
 }
 
 pub trait __internal__DeleteMutation<C: JuniperContext + BotanistContext, T, Q> {
-    fn delete(context: &C, id: T) -> FieldResult<Q>;
+    fn delete(context: &C, executor: &Executor<C, DefaultScalarValue>, id: T) -> FieldResult<Q>;
+
+    fn delete_all(
+        context: &C,
+        executor: &Executor<C, DefaultScalarValue>,
+        ids: Vec<T>,
+    ) -> FieldResult<Vec<Q>>;
 }
 
-pub trait __internal__RootResolver<C: JuniperContext + BotanistContext, T, Q, S> {
-    fn resolve_single(context: &C, id: T) -> FieldResult<Q>;
+pub trait __internal__RootResolver<C: JuniperContext + BotanistContext, T, Q, R, O, F, S> {
+    fn resolve_single(context: &C, executor: &Executor<C, S>, id: T) -> FieldResult<Q>;
 
+    #[allow(clippy::too_many_arguments)]
     fn resolve_multiple(
         context: &C,
         executor: &Executor<C, S>,
         ids: Option<Vec<T>>,
         limit: Option<i32>,
         offset: Option<i32>,
+        order_by: Option<Vec<O>>,
+        where_filter: Option<F>,
         search_query: Option<HashMap<String, String>>,
     ) -> FieldResult<Vec<Q>>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn resolve_connection(
+        context: &C,
+        executor: &Executor<C, S>,
+        ids: Option<Vec<T>>,
+        where_filter: Option<F>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        search_query: Option<HashMap<String, String>>,
+    ) -> FieldResult<R>;
 }
 
 pub trait __internal__DefaultQueryModifier<T, C: JuniperContext + BotanistContext> {
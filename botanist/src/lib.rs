@@ -5,8 +5,10 @@ extern crate diesel;
 use juniper::FieldError;
 use std::marker::PhantomData;
 
+pub mod connection;
 pub mod diesel_extensions;
 pub mod internal;
+pub mod loader;
 pub mod macro_helpers;
 
 #[derive(Debug, Clone)]
@@ -23,13 +25,77 @@ pub struct HasMany<S, F, M> {
     model: PhantomData<M>,
 }
 
+#[derive(Debug, Clone)]
+pub struct HasManyThrough<J, L, R, M> {
+    join_schema: PhantomData<J>,
+    local_key: PhantomData<L>,
+    remote_key: PhantomData<R>,
+    model: PhantomData<M>,
+}
+
+#[derive(juniper::GraphQLEnum, Debug, Clone, Copy, PartialEq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
 pub trait Context {
     type DB;
     type Connection;
 
-    fn get_connection<'a>(&'a self) -> Self::Connection;
+    // Whether the backing database supports `ROW_NUMBER() OVER (PARTITION BY ...)`. Postgres and SQLite do, if
+    // yours doesn't, preloading falls back to loading every child row and paginating them in memory
+    const SUPPORTS_WINDOW_FUNCTIONS: bool = true;
+
+    fn get_connection(&self) -> Self::Connection;
+
+    // Contexts are request scoped, as should be their loader (e.g `loader: Loader::default()` alongside the connection)
+    fn get_loader(&self) -> &loader::Loader;
+}
+
+// With the async feature, resolvers are async and hand their (blocking) Diesel work to the context. The work borrows
+// from the request, so it isn't 'static and can't be moved to another thread outright (i.e with
+// `tokio::task::spawn_blocking`). On a multi-threaded Tokio runtime, `Box::pin(async move {
+// tokio::task::block_in_place(work) })` keeps it from stalling the executor; `block_in_place` panics on a current
+// thread runtime, where the work has to run inline instead
+#[cfg(feature = "async")]
+pub trait AsyncContext: Context + Sync {
+    fn spawn_blocking<'a, R: Send + 'a>(
+        &'a self,
+        work: Box<dyn FnOnce() -> R + Send + 'a>,
+    ) -> juniper::BoxFuture<'a, R>;
+}
+
+// Implemented on models declared with `ModifiesQuery = true`, `T` being the generated `<Model>Query` type. Every query
+// that loads the model (root resolvers, relationships, filters and aggregates) runs through it
+pub trait QueryModifier<T, C: Context> {
+    fn modify_query(query: T, context: &C) -> Result<T, FieldError>;
+}
+
+// Mutation hooks, implemented on models declared with the matching option (i.e `BeforeCreate = true`). Hooks run in the
+// mutation's transaction and are handed its connection, returning an error rejects the mutation and rolls it back
+pub trait BeforeCreate<T, C: Context> {
+    // Returns the input to insert, `T` being the generated `Create<Model>Input`
+    fn before_create(input: T, context: &C, connection: &C::Connection) -> Result<T, FieldError>;
+}
+
+pub trait AfterCreate<C: Context> {
+    fn after_create(&self, context: &C, connection: &C::Connection) -> Result<(), FieldError>;
+}
+
+pub trait BeforeUpdate<T, C: Context> {
+    // Returns the changes to apply, `T` being the generated `Update<Model>Input`
+    fn before_update(input: T, context: &C, connection: &C::Connection) -> Result<T, FieldError>;
+}
+
+pub trait AfterUpdate<C: Context> {
+    fn after_update(&self, context: &C, connection: &C::Connection) -> Result<(), FieldError>;
+}
+
+pub trait BeforeDelete<K, C: Context> {
+    fn before_delete(id: &K, context: &C, connection: &C::Connection) -> Result<(), FieldError>;
 }
 
-pub trait QueryModifier<T, R, C: Context> {
-    fn modify_query(query: T, context: &C) -> Result<R, FieldError>;
+pub trait AfterDelete<C: Context> {
+    fn after_delete(&self, context: &C, connection: &C::Connection) -> Result<(), FieldError>;
 }
@@ -0,0 +1,165 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::Mutex;
+
+type Entries = HashMap<String, Box<dyn Any + Send>>;
+
+// A request scoped cache shared by every relationship resolver. Whenever models are handed out, the keys their
+// relationships will be resolved by are deferred; the first resolver to miss the cache loads every deferred key of its
+// relation at once, later resolvers (and repeats of the same key) are served from the cache
+#[derive(Default)]
+pub struct Loader {
+    // Deferred keys, by relation
+    deferred: Mutex<Entries>,
+    // Loaded values, by relation (and the arguments they were loaded with) then key. Keys without a value are
+    // remembered too, so missing rows aren't looked for twice
+    loaded: Mutex<Entries>,
+}
+
+impl Loader {
+    pub fn defer<K, I>(&self, relation: &str, keys: I)
+    where
+        K: Eq + Hash + Send + 'static,
+        I: IntoIterator<Item = K>,
+    {
+        if let Ok(mut deferred) = self.deferred.lock() {
+            entry::<HashSet<K>>(&mut deferred, relation).extend(keys);
+        }
+    }
+
+    // Returns the value of a key, loading it along with every deferred key of the relation that isn't loaded yet.
+    // `variant` distinguishes loads of the same relation with different arguments (or selections)
+    pub fn load<K, V, E, F>(
+        &self,
+        relation: &str,
+        variant: &str,
+        key: &K,
+        load: F,
+    ) -> Result<Option<V>, E>
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        V: Clone + Send + 'static,
+        F: FnOnce(&[K]) -> Result<HashMap<K, V>, E>,
+    {
+        if let Some(value) = self.cached::<K, V>(variant, key) {
+            return Ok(value);
+        }
+
+        let mut keys = vec![key.clone()];
+
+        if let (Ok(deferred), Ok(mut loaded)) = (self.deferred.lock(), self.loaded.lock()) {
+            let loaded = entry::<HashMap<K, Option<V>>>(&mut loaded, variant);
+
+            if let Some(deferred) = deferred
+                .get(relation)
+                .and_then(|keys| keys.downcast_ref::<HashSet<K>>())
+            {
+                keys.extend(
+                    deferred
+                        .iter()
+                        .filter(|deferred_key| {
+                            *deferred_key != key && !loaded.contains_key(deferred_key)
+                        })
+                        .cloned(),
+                );
+            }
+        }
+
+        // Loading may defer more keys, so nothing can be locked while it runs
+        let values = load(&keys)?;
+        let value = values.get(key).cloned();
+
+        self.store(variant, &keys, values);
+
+        Ok(value)
+    }
+
+    // Returns the values of several keys at once, loading the ones that aren't loaded yet together
+    pub fn load_many<K, V, E, F>(
+        &self,
+        variant: &str,
+        keys: &[K],
+        load: F,
+    ) -> Result<HashMap<K, V>, E>
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        V: Clone + Send + 'static,
+        F: FnOnce(&[K]) -> Result<HashMap<K, V>, E>,
+    {
+        let mut values = HashMap::new();
+        let mut missing = Vec::new();
+
+        for key in keys.iter() {
+            match self.cached::<K, V>(variant, key) {
+                Some(Some(value)) => {
+                    values.insert(key.clone(), value);
+                }
+                Some(None) => {}
+                None => missing.push(key.clone()),
+            }
+        }
+
+        if !missing.is_empty() {
+            let loaded_values = load(&missing)?;
+
+            self.store(variant, &missing, loaded_values.clone());
+
+            values.extend(loaded_values);
+        }
+
+        Ok(values)
+    }
+
+    // Forgets everything deferred and loaded so far, i.e after a mutation
+    pub fn clear(&self) {
+        if let Ok(mut deferred) = self.deferred.lock() {
+            deferred.clear();
+        }
+
+        if let Ok(mut loaded) = self.loaded.lock() {
+            loaded.clear();
+        }
+    }
+
+    fn cached<K: Eq + Hash + Send + 'static, V: Clone + Send + 'static>(
+        &self,
+        variant: &str,
+        key: &K,
+    ) -> Option<Option<V>> {
+        self.loaded.lock().ok().and_then(|loaded| {
+            loaded
+                .get(variant)
+                .and_then(|values| values.downcast_ref::<HashMap<K, Option<V>>>())
+                .and_then(|values| values.get(key).cloned())
+        })
+    }
+
+    fn store<K: Eq + Hash + Clone + Send + 'static, V: Send + 'static>(
+        &self,
+        variant: &str,
+        keys: &[K],
+        mut values: HashMap<K, V>,
+    ) {
+        if let Ok(mut loaded) = self.loaded.lock() {
+            let loaded = entry::<HashMap<K, Option<V>>>(&mut loaded, variant);
+
+            for key in keys.iter() {
+                loaded.insert(key.clone(), values.remove(key));
+            }
+        }
+    }
+}
+
+fn entry<'a, T: Default + Send + 'static>(entries: &'a mut Entries, name: &str) -> &'a mut T {
+    let entry = entries
+        .entry(name.to_string())
+        .or_insert_with(|| Box::new(T::default()));
+
+    // Names are only ever used with a single type, but a mismatch shouldn't be fatal
+    if !entry.is::<T>() {
+        *entry = Box::new(T::default());
+    }
+
+    entry.downcast_mut::<T>().unwrap()
+}
@@ -1,5 +1,6 @@
 use juniper::{
-    DefaultScalarValue, LookAheadMethods, LookAheadSelection, LookAheadValue, ScalarValue,
+    DefaultScalarValue, FromInputValue, InputValue, LookAheadMethods, LookAheadSelection,
+    LookAheadValue, ScalarValue, Spanning,
 };
 
 pub fn int_argument_from_look_ahead(
@@ -18,3 +19,102 @@ pub fn int_argument_from_look_ahead(
         })
         .unwrap_or(default)
 }
+
+// Identifies a selection by its response name, its arguments and those of everything selected below it. Loads are only
+// shared between selections with the same key, as the children they preload depend on all three
+pub fn selection_key(look_ahead: &LookAheadSelection<DefaultScalarValue>) -> String {
+    let mut key = look_ahead.field_name().to_string();
+
+    if look_ahead.has_arguments() {
+        let arguments = look_ahead
+            .arguments()
+            .iter()
+            .map(|argument| format!("{}: {}", argument.name(), input_value_from_look_ahead(argument.value())))
+            .collect::<Vec<String>>();
+
+        key.push_str(&format!("({})", arguments.join(", ")));
+    }
+
+    if look_ahead.has_children() {
+        let children = look_ahead
+            .children()
+            .into_iter()
+            .map(selection_key)
+            .collect::<Vec<String>>();
+
+        key.push_str(&format!(" {{ {} }}", children.join(" ")));
+    }
+
+    key
+}
+
+// Selects the children of the look ahead for the given field. Juniper only exposes a selection's response name, so
+// aliased selections aren't matched, they're left to their resolvers which load them through the loader (batched
+// with every other deferred parent) instead
+pub fn select_children<'a, 'b>(
+    look_ahead: &'b LookAheadSelection<'a, DefaultScalarValue>,
+    field: &str,
+) -> Vec<&'b LookAheadSelection<'a, DefaultScalarValue>> {
+    look_ahead
+        .children()
+        .into_iter()
+        .filter(|child| child.field_name() == field)
+        .collect()
+}
+
+// Connection fields are told apart by their shape rather than their (possibly aliased) names: `totalCount` is the only
+// scalar of a connection besides `__typename`
+pub fn selects_total_count(look_ahead: &LookAheadSelection<DefaultScalarValue>) -> bool {
+    look_ahead
+        .children()
+        .into_iter()
+        .any(|child| !child.has_children() && child.field_name() != "__typename")
+}
+
+// Likewise, `node` is the only object selected on `edges` (`pageInfo` only has scalars)
+pub fn select_connection_nodes<'a, 'b>(
+    look_ahead: &'b LookAheadSelection<'a, DefaultScalarValue>,
+) -> Vec<&'b LookAheadSelection<'a, DefaultScalarValue>> {
+    look_ahead
+        .children()
+        .into_iter()
+        .flat_map(|child| child.children())
+        .filter(|grandchild| grandchild.has_children())
+        .collect()
+}
+
+// Look ahead arguments are already resolved (variables included), so they can be converted back to an InputValue and
+// parsed into the argument's actual type
+pub fn input_argument_from_look_ahead<T: FromInputValue>(
+    look_ahead: &LookAheadSelection<DefaultScalarValue>,
+    argument: &str,
+) -> Option<T> {
+    look_ahead
+        .argument(argument)
+        .and_then(|arg| T::from_input_value(&input_value_from_look_ahead(arg.value())))
+}
+
+fn input_value_from_look_ahead(value: &LookAheadValue<DefaultScalarValue>) -> InputValue {
+    match value {
+        LookAheadValue::Null => InputValue::Null,
+        LookAheadValue::Scalar(scalar) => InputValue::Scalar((*scalar).clone()),
+        LookAheadValue::Enum(value) => InputValue::Enum(value.to_string()),
+        LookAheadValue::List(values) => InputValue::List(
+            values
+                .iter()
+                .map(|value| Spanning::unlocated(input_value_from_look_ahead(value)))
+                .collect(),
+        ),
+        LookAheadValue::Object(fields) => InputValue::Object(
+            fields
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        Spanning::unlocated(name.to_string()),
+                        Spanning::unlocated(input_value_from_look_ahead(value)),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,90 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn counts_models_matching_a_filter() {
+    let database = Database::connect();
+
+    assert_eq!(
+        database.field("{ heroCount }", "heroCount"),
+        graphql_value!(4)
+    );
+
+    assert_eq!(
+        database.field("{ heroCount(where: { age: { gt: 30 } }) }", "heroCount"),
+        graphql_value!(3)
+    );
+
+    assert_eq!(
+        database.field("{ locationCount }", "locationCount"),
+        graphql_value!(3)
+    );
+}
+
+#[test]
+fn aggregates_numeric_columns() {
+    let database = Database::connect();
+
+    let aggregate = database.field(
+        "{ heroAggregate { count avg { age } sum { age } min { age } max { age } } }",
+        "heroAggregate",
+    );
+
+    assert_eq!(
+        aggregate,
+        graphql_value!({
+            "count": 4,
+            "avg": { "age": 32.75 },
+            "sum": { "age": 131.0 },
+            "min": { "age": 18.0 },
+            "max": { "age": 40.0 },
+        })
+    );
+}
+
+#[test]
+fn aggregates_are_null_without_any_models() {
+    let database = Database::connect();
+
+    let aggregate = database.field(
+        "{ heroAggregate(where: { age: { gt: 100 } }) { count avg { age } } }",
+        "heroAggregate",
+    );
+
+    assert_eq!(
+        aggregate,
+        graphql_value!({ "count": 0, "avg": { "age": None } })
+    );
+}
+
+#[test]
+fn aggregates_apply_the_query_modifier() {
+    let database = Database::connect().with_max_enemy_power(9100);
+
+    // Bane, Zod and Deathstroke are hidden
+    let aggregate = database.field(
+        "{ enemyAggregate { count max { power } } }",
+        "enemyAggregate",
+    );
+
+    assert_eq!(
+        aggregate,
+        graphql_value!({ "count": 6, "max": { "power": 9001.0 } })
+    );
+
+    assert_eq!(
+        database.field(
+            "{ enemyCount(where: { power: { gt: 9000 } }) }",
+            "enemyCount"
+        ),
+        graphql_value!(1)
+    );
+}
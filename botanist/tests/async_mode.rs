@@ -0,0 +1,56 @@
+// Only meaningful with `--features async,botanist_codegen/async`, the rest of the suite covers the resolvers themselves
+#![cfg(feature = "async")]
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+use std::sync::Arc;
+
+#[test]
+fn resolves_concurrently_on_a_multi_threaded_runtime() {
+    let database = Arc::new(Database::connect());
+
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start runtime");
+
+    // Spawning requires the resolvers' futures to be Send
+    let handles = (1..=4)
+        .map(|id| {
+            let database = database.clone();
+
+            runtime.spawn(async move {
+                database
+                    .execute_async(&format!(
+                        "{{ hero(id: {}) {{ name locationId {{ name }} enemiesCount }} }}",
+                        id
+                    ))
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let heros = runtime.block_on(async {
+        let mut heros = Vec::new();
+
+        for handle in handles {
+            heros.push(handle.await.expect("resolver task panicked"));
+        }
+
+        heros
+    });
+
+    assert_eq!(
+        heros,
+        vec![
+            graphql_value!({ "hero": { "name": "Batman", "locationId": { "name": "Gotham" }, "enemiesCount": 4 } }),
+            graphql_value!({ "hero": { "name": "Superman", "locationId": { "name": "Metropolis" }, "enemiesCount": 3 } }),
+            graphql_value!({ "hero": { "name": "Green Arrow", "locationId": { "name": "Star City" }, "enemiesCount": 2 } }),
+            graphql_value!({ "hero": { "name": "Robin", "locationId": { "name": "Gotham" }, "enemiesCount": 0 } }),
+        ]
+    );
+}
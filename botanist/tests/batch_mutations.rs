@@ -0,0 +1,152 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::{graphql_value, DefaultScalarValue, Value};
+
+fn ids_of(value: &Value<DefaultScalarValue>, id: &str) -> Vec<i32> {
+    match value {
+        Value::List(models) => models
+            .iter()
+            .map(|model| {
+                model
+                    .as_object_value()
+                    .and_then(|model| model.get_field_value(id))
+                    .and_then(|id| id.as_scalar_value::<i32>())
+                    .cloned()
+                    .expect("model has no id")
+            })
+            .collect(),
+        _ => panic!("expected a list of models"),
+    }
+}
+
+#[test]
+fn creates_updates_and_deletes_in_batches() {
+    let database = Database::connect();
+
+    let created = database.field(
+        r#"mutation {
+            createBases(inputs: [{ name: "Arkham Asylum", rooms: { create: [{ name: "Cell Block" }] } }, { name: "Blackgate" }]) {
+                id
+                name
+                rooms { name }
+            }
+        }"#,
+        "createBases",
+    );
+    let ids = ids_of(&created, "id");
+
+    assert_eq!(
+        database.field(
+            &format!(
+                r#"mutation {{
+                    updateBases(inputs: [{{ id: {}, name: "Arkham" }}, {{ id: {}, name: "Blackgate Penitentiary" }}]) {{
+                        name
+                        rooms {{ name }}
+                    }}
+                }}"#,
+                ids[0], ids[1]
+            ),
+            "updateBases"
+        ),
+        graphql_value!([
+            { "name": "Arkham", "rooms": [{ "name": "Cell Block" }] },
+            { "name": "Blackgate Penitentiary", "rooms": [] },
+        ])
+    );
+
+    // Keys that don't exist are skipped
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ deleteBases(ids: [{}, 0]) {{ name }} }}",
+                ids[1]
+            ),
+            "deleteBases"
+        ),
+        graphql_value!([{ "name": "Blackgate Penitentiary" }])
+    );
+}
+
+#[test]
+fn batches_roll_back_together() {
+    let database = Database::connect();
+
+    let base = ids_of(
+        &database.field(
+            r#"mutation { createBases(inputs: [{ name: "Belle Reve" }]) { id } }"#,
+            "createBases",
+        ),
+        "id",
+    )[0];
+
+    let (rooms, errors) = database.try_execute(&format!(
+        r#"mutation {{ createRooms(inputs: [{{ name: "Vault", baseId: {0} }}, {{ name: "", baseId: {0} }}]) {{ name }} }}"#,
+        base
+    ));
+
+    assert_eq!(rooms, graphql_value!(None));
+    assert_eq!(errors.len(), 1);
+
+    assert_eq!(
+        database.field(
+            &format!("{{ base(id: {}) {{ rooms {{ name }} }} }}", base),
+            "base"
+        ),
+        graphql_value!({ "rooms": [] })
+    );
+}
+
+#[test]
+fn batches_run_hooks_for_each_model() {
+    let database = Database::connect();
+
+    let created = database.field(
+        r#"mutation { createTeams(inputs: [{ name: " Suicide Squad " }, { name: "Doom Patrol" }]) { teamId name } }"#,
+        "createTeams",
+    );
+    let ids = ids_of(&created, "teamId");
+
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ deleteTeams(ids: [{}, {}]) {{ name }} }}",
+                ids[0], ids[1]
+            ),
+            "deleteTeams"
+        ),
+        graphql_value!([{ "name": "Suicide Squad" }, { "name": "Doom Patrol" }])
+    );
+
+    // Both teams are inserted before either after hook runs, so each sees the other
+    let (created, errors) = database.try_execute(
+        r#"mutation { createTeams(inputs: [{ name: "Legion" }, { name: "Legion" }]) { name } }"#,
+    );
+
+    assert_eq!(created, graphql_value!(None));
+    assert_eq!(errors, vec!["Team names must be unique"]);
+
+    assert_eq!(
+        database.field(
+            r#"{ teams(where: { name: { eq: "Legion" } }) { teamId } }"#,
+            "teams"
+        ),
+        graphql_value!([])
+    );
+
+    assert_eq!(
+        database.audit(),
+        vec![
+            "created Suicide Squad",
+            "created Doom Patrol",
+            "deleted Suicide Squad",
+            "deleted Doom Patrol"
+        ]
+    );
+}
@@ -0,0 +1,26 @@
+DROP TABLE IF EXISTS rooms, bases, team_memberships, teams, enemies, heros, locations CASCADE;
+
+CREATE TABLE locations (code SERIAL PRIMARY KEY, name TEXT NOT NULL);
+CREATE TABLE heros (id SERIAL PRIMARY KEY, name TEXT NOT NULL, age INT NOT NULL, location_id INT NOT NULL REFERENCES locations(code), mentor_id INT);
+CREATE TABLE enemies (id SERIAL PRIMARY KEY, name TEXT NOT NULL, power INT NOT NULL, hero_id INT NOT NULL REFERENCES heros(id), location_id INT NOT NULL REFERENCES locations(code));
+CREATE TABLE teams (team_id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+CREATE TABLE team_memberships (hero_id INT NOT NULL REFERENCES heros(id), team_id INT NOT NULL REFERENCES teams(team_id), role TEXT NOT NULL, PRIMARY KEY (hero_id, team_id));
+CREATE TABLE bases (id SERIAL PRIMARY KEY, name TEXT NOT NULL, code TEXT UNIQUE, commander_id INT REFERENCES heros(id));
+CREATE TABLE rooms (id SERIAL PRIMARY KEY, name TEXT NOT NULL CHECK (name <> ''), base_id INT NOT NULL REFERENCES bases(id));
+
+INSERT INTO locations (code, name) VALUES (3, 'Metropolis'), (1, 'Gotham'), (2, 'Star City');
+SELECT setval(pg_get_serial_sequence('locations', 'code'), 3);
+INSERT INTO heros (id, name, age, location_id) VALUES (1, 'Batman', 40, 1), (2, 'Superman', 35, 3), (3, 'Green Arrow', 38, 2), (4, 'Robin', 18, 1);
+UPDATE heros SET mentor_id = 1 WHERE id = 4;
+-- mentor_id isn't a forign key, Superman's mentor (hero 0) doesn't exist
+UPDATE heros SET mentor_id = 0 WHERE id = 2;
+INSERT INTO enemies (id, name, power, hero_id, location_id) VALUES
+    (1, 'Joker', 50, 1, 1), (2, 'Bane', 9500, 1, 1), (3, 'Riddler', 20, 1, 1), (4, 'Penguin', 30, 1, 1),
+    (5, 'Lex Luthor', 100, 2, 3), (6, 'Zod', 9900, 2, 3), (7, 'Brainiac', 9001, 2, 2),
+    (8, 'Merlyn', 60, 3, 2), (9, 'Deathstroke', 9200, 3, 2);
+INSERT INTO teams (team_id, name) VALUES (1, 'Justice League'), (2, 'Bat Family'), (3, 'Titans');
+SELECT setval(pg_get_serial_sequence('teams', 'team_id'), 3);
+INSERT INTO team_memberships (hero_id, team_id, role) VALUES
+    (1, 1, 'Founder'), (2, 1, 'Founder'), (3, 1, 'Member'), (1, 2, 'Mentor'), (4, 2, 'Sidekick'), (4, 3, 'Leader');
+INSERT INTO bases (id, name) VALUES (1, 'Watchtower');
+SELECT setval(pg_get_serial_sequence('bases', 'id'), 1);
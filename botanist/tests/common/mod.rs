@@ -0,0 +1,597 @@
+// Shared schema, models and helpers for the integration tests. The tests run against the Postgres database at
+// DATABASE_URL and fail without it.
+
+use botanist::loader::Loader;
+use diesel::connection::SimpleConnection;
+use diesel::pg::PgConnection;
+use diesel::Connection;
+use juniper::{DefaultScalarValue, EmptySubscription, Value, Variables};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+
+pub mod schema {
+    table! {
+        locations (code) {
+            code -> Int4,
+            name -> Text,
+        }
+    }
+
+    table! {
+        heros (id) {
+            id -> Int4,
+            name -> Text,
+            age -> Int4,
+            location_id -> Int4,
+            mentor_id -> Nullable<Int4>,
+        }
+    }
+
+    table! {
+        enemies (id) {
+            id -> Int4,
+            name -> Text,
+            power -> Int4,
+            hero_id -> Int4,
+            location_id -> Int4,
+        }
+    }
+
+    table! {
+        teams (team_id) {
+            team_id -> Int4,
+            name -> Text,
+        }
+    }
+
+    table! {
+        team_memberships (hero_id, team_id) {
+            hero_id -> Int4,
+            team_id -> Int4,
+            role -> Text,
+        }
+    }
+
+    table! {
+        bases (id) {
+            id -> Int4,
+            name -> Text,
+            code -> Nullable<Text>,
+            commander_id -> Nullable<Int4>,
+        }
+    }
+
+    table! {
+        rooms (id) {
+            id -> Int4,
+            name -> Text,
+            base_id -> Int4,
+        }
+    }
+
+    allow_tables_to_appear_in_same_query!(
+        locations,
+        heros,
+        enemies,
+        teams,
+        team_memberships,
+        bases,
+        rooms
+    );
+}
+
+pub struct Context {
+    database_url: String,
+    connections: AtomicUsize,
+    // Enemies more powerful than this are hidden by Enemy's query modifier
+    max_enemy_power: Option<i32>,
+    // A team hidden by Team's query modifier
+    hidden_team: Option<i32>,
+    loader: Loader,
+    // Changes recorded by Team's mutation hooks
+    audit: Mutex<Vec<String>>,
+}
+
+impl juniper::Context for Context {}
+
+impl Context {
+    fn record(&self, change: String) {
+        self.audit.lock().expect("audit lock poisoned").push(change);
+    }
+}
+
+impl botanist::Context for Context {
+    type DB = diesel::pg::Pg;
+    type Connection = PgConnection;
+
+    fn get_connection(&self) -> PgConnection {
+        self.connections.fetch_add(1, Ordering::SeqCst);
+        PgConnection::establish(&self.database_url).expect("failed to connect to DATABASE_URL")
+    }
+
+    fn get_loader(&self) -> &Loader {
+        &self.loader
+    }
+}
+
+#[cfg(feature = "async")]
+impl botanist::AsyncContext for Context {
+    fn spawn_blocking<'a, R: Send + 'a>(
+        &'a self,
+        work: Box<dyn FnOnce() -> R + Send + 'a>,
+    ) -> juniper::BoxFuture<'a, R> {
+        Box::pin(async move { tokio::task::block_in_place(work) })
+    }
+}
+
+pub mod location {
+    use super::schema::*;
+    use super::Context;
+    use botanist_codegen::botanist_object;
+
+    #[botanist_object(Context = Context)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "locations"]
+    #[primary_key(code)]
+    pub struct Location {
+        pub code: i32,
+        pub name: String,
+    }
+}
+
+pub mod hero {
+    use super::enemy::{Enemy, EnemyConnection, EnemyGQL};
+    use super::location::{Location, LocationGQL};
+    use super::schema::*;
+    use super::team::{Team, TeamGQL};
+    use super::Context;
+    use botanist_codegen::botanist_object;
+
+    #[botanist_object(Context = Context)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "heros"]
+    pub struct Hero {
+        pub id: i32,
+        pub name: String,
+        pub age: i32,
+        pub location_id: HasOne<i32, locations, Location>,
+        pub mentor_id: HasOne<Option<i32>, heros, Hero>,
+        #[botanist(connection = true)]
+        pub enemies: HasMany<enemies, enemies::hero_id, Enemy>,
+        pub teams: HasManyThrough<
+            team_memberships,
+            team_memberships::hero_id,
+            team_memberships::team_id,
+            Team,
+        >,
+    }
+}
+
+pub mod enemy {
+    use super::location::{Location, LocationGQL};
+    use super::schema::*;
+    use super::Context;
+    use botanist::QueryModifier;
+    use botanist_codegen::botanist_object;
+    use juniper::FieldError;
+
+    #[botanist_object(Context = Context, ModifiesQuery = true)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "enemies"]
+    pub struct Enemy {
+        pub id: i32,
+        pub name: String,
+        pub power: i32,
+        pub hero_id: i32,
+        pub location_id: HasOne<i32, locations, Location>,
+    }
+
+    impl<'a> QueryModifier<EnemyQuery<'a>, Context> for Enemy {
+        fn modify_query(
+            query: EnemyQuery<'a>,
+            context: &Context,
+        ) -> Result<EnemyQuery<'a>, FieldError> {
+            Ok(match context.max_enemy_power {
+                Some(max_enemy_power) => query.filter(enemies::power.le(max_enemy_power)),
+                None => query,
+            })
+        }
+    }
+}
+
+pub mod team {
+    use super::hero::{Hero, HeroGQL};
+    use super::schema::*;
+    use super::Context;
+    use botanist::{
+        AfterCreate, AfterDelete, AfterUpdate, BeforeCreate, BeforeDelete, BeforeUpdate,
+        QueryModifier,
+    };
+    use botanist_codegen::botanist_object;
+    use diesel::pg::PgConnection;
+    use juniper::FieldError;
+
+    #[botanist_object(
+        Context = Context,
+        ModifiesQuery = true,
+        BeforeCreate = true,
+        AfterCreate = true,
+        BeforeUpdate = true,
+        AfterUpdate = true,
+        BeforeDelete = true,
+        AfterDelete = true
+    )]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "teams"]
+    #[primary_key(team_id)]
+    pub struct Team {
+        pub team_id: i32,
+        pub name: String,
+        pub members: HasManyThrough<
+            team_memberships,
+            team_memberships::team_id,
+            team_memberships::hero_id,
+            Hero,
+        >,
+    }
+
+    impl<'a> QueryModifier<TeamQuery<'a>, Context> for Team {
+        fn modify_query(query: TeamQuery<'a>, context: &Context) -> Result<TeamQuery<'a>, FieldError> {
+            Ok(match context.hidden_team {
+                Some(team_id) => query.filter(teams::team_id.ne(team_id)),
+                None => query,
+            })
+        }
+    }
+
+    // Names are trimmed and must be unique, teams with members can't be deleted. Every change is audited
+    impl BeforeCreate<CreateTeamInput, Context> for Team {
+        fn before_create(
+            mut input: CreateTeamInput,
+            _context: &Context,
+            _connection: &PgConnection,
+        ) -> Result<CreateTeamInput, FieldError> {
+            input.name = input.name.trim().to_string();
+
+            Ok(input)
+        }
+    }
+
+    impl AfterCreate<Context> for Team {
+        fn after_create(
+            &self,
+            context: &Context,
+            connection: &PgConnection,
+        ) -> Result<(), FieldError> {
+            // Runs after the insert, so the new team counts itself
+            let named = teams::table
+                .filter(teams::name.eq(&self.name))
+                .count()
+                .get_result::<i64>(connection)?;
+
+            if named > 1 {
+                return Err("Team names must be unique".into());
+            }
+
+            context.record(format!("created {}", self.name));
+
+            Ok(())
+        }
+    }
+
+    impl BeforeUpdate<UpdateTeamInput, Context> for Team {
+        fn before_update(
+            mut input: UpdateTeamInput,
+            _context: &Context,
+            _connection: &PgConnection,
+        ) -> Result<UpdateTeamInput, FieldError> {
+            input.name = input.name.map(|name| name.trim().to_string());
+
+            Ok(input)
+        }
+    }
+
+    impl AfterUpdate<Context> for Team {
+        fn after_update(
+            &self,
+            context: &Context,
+            _connection: &PgConnection,
+        ) -> Result<(), FieldError> {
+            context.record(format!("updated {}", self.name));
+
+            Ok(())
+        }
+    }
+
+    impl BeforeDelete<i32, Context> for Team {
+        fn before_delete(
+            id: &i32,
+            _context: &Context,
+            connection: &PgConnection,
+        ) -> Result<(), FieldError> {
+            let members = team_memberships::table
+                .filter(team_memberships::team_id.eq(id))
+                .count()
+                .get_result::<i64>(connection)?;
+
+            if members > 0 {
+                return Err("Teams with members can't be deleted".into());
+            }
+
+            Ok(())
+        }
+    }
+
+    impl AfterDelete<Context> for Team {
+        fn after_delete(
+            &self,
+            context: &Context,
+            _connection: &PgConnection,
+        ) -> Result<(), FieldError> {
+            context.record(format!("deleted {}", self.name));
+
+            Ok(())
+        }
+    }
+}
+
+pub mod team_membership {
+    use super::hero::{Hero, HeroGQL};
+    use super::schema::*;
+    use super::team::{Team, TeamGQL};
+    use super::Context;
+    use botanist_codegen::botanist_object;
+
+    #[botanist_object(Context = Context)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "team_memberships"]
+    #[primary_key(hero_id, team_id)]
+    pub struct TeamMembership {
+        pub hero_id: HasOne<i32, heros, Hero>,
+        pub team_id: HasOne<i32, teams, Team>,
+        pub role: String,
+    }
+}
+
+// Only written to by the nested and relationship mutation tests, so nothing else is affected by what they change
+pub mod base {
+    use super::hero::{Hero, HeroGQL};
+    use super::room::{Room, RoomGQL};
+    use super::schema::*;
+    use super::Context;
+    use botanist_codegen::botanist_object;
+
+    #[botanist_object(Context = Context)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "bases"]
+    pub struct Base {
+        pub id: i32,
+        pub name: String,
+        pub code: Option<String>,
+        pub commander_id: HasOne<Option<i32>, heros, Hero>,
+        pub rooms: HasMany<rooms, rooms::base_id, Room>,
+    }
+}
+
+pub mod room {
+    use super::base::{Base, BaseGQL};
+    use super::schema::*;
+    use super::Context;
+    use botanist_codegen::botanist_object;
+
+    #[botanist_object(Context = Context)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "rooms"]
+    pub struct Room {
+        pub id: i32,
+        pub name: String,
+        pub base_id: HasOne<i32, bases, Base>,
+    }
+}
+
+pub struct Query;
+pub struct Mutation;
+
+mod root {
+    use super::base::*;
+    use super::enemy::*;
+    use super::hero::*;
+    use super::location::*;
+    use super::room::*;
+    use super::team::*;
+    use super::team_membership::*;
+    use super::{Context, Mutation, Query};
+    use botanist_codegen::{botanist_mutation, botanist_query};
+
+    #[botanist_query(
+        Hero(all = true, connection = true, aggregates = true, searchable = (name)),
+        Enemy(plural = "Enemies", connection = true, aggregates = true),
+        Location(aggregates = true),
+        Team(all = true),
+        TeamMembership(all = true, connection = true),
+        Base(all = true),
+
+        Context = Context,
+        PrimaryKey = i32,
+    )]
+    impl Query {
+        // Hand written, so nothing it returns is preloaded. `defer` hands the heros to the loader
+        fn heros_by_age(context: &Context, defer: bool) -> juniper::FieldResult<Vec<HeroGQL>> {
+            use diesel::prelude::*;
+
+            let heros = super::schema::heros::table
+                .order(super::schema::heros::age)
+                .load::<Hero>(&botanist::Context::get_connection(context))?
+                .into_iter()
+                .map(HeroGQL::from)
+                .collect::<Vec<HeroGQL>>();
+
+            if defer {
+                HeroGQL::defer_children(&heros, context);
+            }
+
+            Ok(heros)
+        }
+    }
+
+    // Upserts are only generated with the postgres_upsert feature
+    #[cfg_attr(
+        feature = "postgres_upsert",
+        botanist_mutation(
+            Hero,
+            Enemy,
+            Location,
+            Team(batch = true),
+            TeamMembership(upsert = true),
+            Base(relations = (rooms, commander_id), batch = true, upsert = true),
+            Room(relations = (base_id), batch = true),
+
+            Context = Context,
+            PrimaryKey = i32,
+        )
+    )]
+    #[cfg_attr(
+        not(feature = "postgres_upsert"),
+        botanist_mutation(
+            Hero,
+            Enemy,
+            Location,
+            Team(batch = true),
+            TeamMembership,
+            Base(relations = (rooms, commander_id), batch = true),
+            Room(relations = (base_id), batch = true),
+
+            Context = Context,
+            PrimaryKey = i32,
+        )
+    )]
+    impl Mutation {}
+}
+
+pub type Schema = juniper::RootNode<'static, Query, Mutation, EmptySubscription<Context>>;
+
+pub struct Database {
+    context: Context,
+    schema: Schema,
+}
+
+static FIXTURES: Once = Once::new();
+
+impl Database {
+    // Connects to DATABASE_URL and loads the fixtures. A missing database fails the test rather than skipping it, so a
+    // run without one can't pass unnoticed
+    pub fn connect() -> Database {
+        let database_url = std::env::var("DATABASE_URL")
+            .expect("DATABASE_URL must be set to a Postgres database for the integration tests");
+
+        FIXTURES.call_once(|| {
+            PgConnection::establish(&database_url)
+                .expect("failed to connect to DATABASE_URL")
+                .batch_execute(include_str!("fixtures.sql"))
+                .expect("failed to load fixtures");
+        });
+
+        Database {
+            context: Context {
+                database_url,
+                connections: AtomicUsize::new(0),
+                max_enemy_power: None,
+                hidden_team: None,
+                loader: Loader::default(),
+                audit: Mutex::new(Vec::new()),
+            },
+            schema: Schema::new(Query, Mutation, EmptySubscription::new()),
+        }
+    }
+
+    // Hides enemies more powerful than `max_enemy_power` through Enemy's query modifier
+    #[allow(dead_code)]
+    pub fn with_max_enemy_power(mut self, max_enemy_power: i32) -> Database {
+        self.context.max_enemy_power = Some(max_enemy_power);
+        self
+    }
+
+    // Hides the team with `team_id` through Team's query modifier
+    #[allow(dead_code)]
+    pub fn with_hidden_team(mut self, team_id: i32) -> Database {
+        self.context.hidden_team = Some(team_id);
+        self
+    }
+
+    // Executes a query, returning its data and the number of connections (i.e queries) it used
+    #[allow(dead_code)]
+    pub fn execute(&self, query: &str) -> (Value<DefaultScalarValue>, usize) {
+        self.context.connections.store(0, Ordering::SeqCst);
+
+        let (value, errors) = self.try_execute(query);
+
+        assert!(errors.is_empty(), "query returned errors: {:?}", errors);
+
+        (value, self.context.connections.load(Ordering::SeqCst))
+    }
+
+    // Executes a query that may fail, returning its data and the messages of any errors it raised
+    pub fn try_execute(&self, query: &str) -> (Value<DefaultScalarValue>, Vec<String>) {
+        // Every execution is a request of its own, nothing should be loaded from a previous one
+        self.context.loader.clear();
+
+        #[cfg(not(feature = "async"))]
+        let (value, errors) =
+            juniper::execute_sync(query, None, &self.schema, &Variables::new(), &self.context)
+                .expect("query failed to execute");
+
+        // Async resolvers block in place, which needs a multi-threaded runtime
+        #[cfg(feature = "async")]
+        let (value, errors) = tokio::runtime::Runtime::new()
+            .expect("failed to start runtime")
+            .block_on(juniper::execute(
+                query,
+                None,
+                &self.schema,
+                &Variables::new(),
+                &self.context,
+            ))
+            .expect("query failed to execute");
+
+        let errors = errors
+            .iter()
+            .map(|error| error.error().message().to_string())
+            .collect();
+
+        (value, errors)
+    }
+
+    // Changes recorded by Team's mutation hooks so far
+    #[allow(dead_code)]
+    pub fn audit(&self) -> Vec<String> {
+        self.context
+            .audit
+            .lock()
+            .expect("audit lock poisoned")
+            .clone()
+    }
+
+    #[cfg(feature = "async")]
+    #[allow(dead_code)]
+    pub async fn execute_async(&self, query: &str) -> Value<DefaultScalarValue> {
+        let (value, errors) =
+            juniper::execute(query, None, &self.schema, &Variables::new(), &self.context)
+                .await
+                .expect("query failed to execute");
+
+        assert!(errors.is_empty(), "query returned errors: {:?}", errors);
+
+        value
+    }
+
+    // Executes a query and returns the value of a single root field
+    #[allow(dead_code)]
+    pub fn field(&self, query: &str, field: &str) -> Value<DefaultScalarValue> {
+        self.execute(query)
+            .0
+            .as_object_value()
+            .and_then(|object| object.get_field_value(field))
+            .cloned()
+            .unwrap_or_else(|| panic!("missing root field {}", field))
+    }
+}
@@ -0,0 +1,14 @@
+// Misuse of the macros that should be caught at compile time, each case's expected errors sit alongside it. Async
+// resolvers fail differently, so these only run without the async feature
+#![cfg(not(feature = "async"))]
+
+#[test]
+fn compile_fail() {
+    let cases = trybuild::TestCases::new();
+
+    cases.compile_fail("tests/compile_fail/*.rs");
+
+    // Opting a model in to upserts is only a mistake without the feature
+    #[cfg(not(feature = "postgres_upsert"))]
+    cases.compile_fail("tests/compile_fail/upsert/*.rs");
+}
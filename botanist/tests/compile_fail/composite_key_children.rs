@@ -0,0 +1,58 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use botanist::loader::Loader;
+use diesel::pg::PgConnection;
+
+table! {
+    memberships (hero_id, team_id) {
+        hero_id -> Int4,
+        team_id -> Int4,
+    }
+}
+
+table! {
+    badges (id) {
+        id -> Int4,
+        membership_id -> Int4,
+    }
+}
+
+pub struct Context {
+    loader: Loader,
+}
+
+impl juniper::Context for Context {}
+
+impl botanist::Context for Context {
+    type DB = diesel::pg::Pg;
+    type Connection = PgConnection;
+
+    fn get_connection(&self) -> PgConnection {
+        unimplemented!()
+    }
+
+    fn get_loader(&self) -> &Loader {
+        &self.loader
+    }
+}
+
+pub mod membership {
+    use botanist_codegen::botanist_object;
+
+    // Badges can't point back at a composite key with their single forign key
+    #[botanist_object(Context = Context)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "memberships"]
+    #[primary_key(hero_id, team_id)]
+    pub struct Membership {
+        pub hero_id: i32,
+        pub team_id: i32,
+        pub badges: HasMany<badges, badges::membership_id, Badge>,
+    }
+}
+
+fn main() {}
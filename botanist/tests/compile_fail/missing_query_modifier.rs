@@ -0,0 +1,51 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use botanist::loader::Loader;
+use diesel::pg::PgConnection;
+
+table! {
+    heros (id) {
+        id -> Int4,
+        name -> Text,
+    }
+}
+
+pub struct Context {
+    loader: Loader,
+}
+
+impl juniper::Context for Context {}
+
+impl botanist::Context for Context {
+    type DB = diesel::pg::Pg;
+    type Connection = PgConnection;
+
+    fn get_connection(&self) -> PgConnection {
+        unimplemented!()
+    }
+
+    fn get_loader(&self) -> &Loader {
+        &self.loader
+    }
+}
+
+pub mod hero {
+    use super::heros;
+    use super::Context;
+    use botanist_codegen::botanist_object;
+
+    // ModifiesQuery without a QueryModifier implementation
+    #[botanist_object(Context = Context, ModifiesQuery = true)]
+    #[derive(Queryable, Identifiable, Clone)]
+    #[table_name = "heros"]
+    pub struct Hero {
+        pub id: i32,
+        pub name: String,
+    }
+}
+
+fn main() {}
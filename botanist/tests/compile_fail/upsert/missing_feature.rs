@@ -0,0 +1,33 @@
+use botanist::loader::Loader;
+use diesel::pg::PgConnection;
+
+pub struct Context {
+    loader: Loader,
+}
+
+impl juniper::Context for Context {}
+
+impl botanist::Context for Context {
+    type DB = diesel::pg::Pg;
+    type Connection = PgConnection;
+
+    fn get_connection(&self) -> PgConnection {
+        unimplemented!()
+    }
+
+    fn get_loader(&self) -> &Loader {
+        &self.loader
+    }
+}
+
+pub mod mutation {
+    use botanist_codegen::botanist_mutation;
+
+    pub struct Mutation;
+
+    // Upserts need `ON CONFLICT`, which only the postgres_upsert feature generates
+    #[botanist_mutation(Badge(upsert = true), Context = Context, PrimaryKey = i32)]
+    impl Mutation {}
+}
+
+fn main() {}
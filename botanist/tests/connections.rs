@@ -0,0 +1,258 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::{graphql_value, DefaultScalarValue, Value};
+
+const PAGE_SELECTION: &str =
+    "totalCount edges { node { name } } pageInfo { hasNextPage hasPreviousPage startCursor endCursor }";
+
+fn page_info_cursor(connection: &Value<DefaultScalarValue>, cursor: &str) -> String {
+    connection
+        .as_object_value()
+        .and_then(|connection| connection.get_field_value("pageInfo"))
+        .and_then(|page_info| page_info.as_object_value())
+        .and_then(|page_info| page_info.get_field_value(cursor))
+        .and_then(|cursor| cursor.as_string_value())
+        .unwrap_or_else(|| panic!("missing {}", cursor))
+        .to_string()
+}
+
+fn names_and_page(
+    connection: &Value<DefaultScalarValue>,
+) -> (Value<DefaultScalarValue>, Value<DefaultScalarValue>) {
+    let connection = connection.as_object_value().unwrap();
+    let page_info = connection
+        .get_field_value("pageInfo")
+        .unwrap()
+        .as_object_value()
+        .unwrap();
+
+    (
+        connection.get_field_value("edges").cloned().unwrap(),
+        graphql_value!({
+            "hasNextPage": (page_info.get_field_value("hasNextPage").cloned().unwrap()),
+            "hasPreviousPage": (page_info.get_field_value("hasPreviousPage").cloned().unwrap()),
+        }),
+    )
+}
+
+#[test]
+fn paginates_forwards_with_first_and_after() {
+    let database = Database::connect();
+
+    let first_page = database.field(
+        &format!("{{ herosConnection(first: 2) {{ {} }} }}", PAGE_SELECTION),
+        "herosConnection",
+    );
+
+    assert_eq!(
+        names_and_page(&first_page),
+        (
+            graphql_value!([{ "node": { "name": "Batman" } }, { "node": { "name": "Superman" } }]),
+            graphql_value!({ "hasNextPage": true, "hasPreviousPage": false }),
+        )
+    );
+
+    let second_page = database.field(
+        &format!(
+            "{{ herosConnection(first: 2, after: {:?}) {{ {} }} }}",
+            page_info_cursor(&first_page, "endCursor"),
+            PAGE_SELECTION
+        ),
+        "herosConnection",
+    );
+
+    assert_eq!(
+        names_and_page(&second_page),
+        (
+            graphql_value!([{ "node": { "name": "Green Arrow" } }, { "node": { "name": "Robin" } }]),
+            graphql_value!({ "hasNextPage": false, "hasPreviousPage": true }),
+        )
+    );
+}
+
+#[test]
+fn paginates_backwards_with_last_and_before() {
+    let database = Database::connect();
+
+    let last_page = database.field(
+        &format!("{{ herosConnection(last: 3) {{ {} }} }}", PAGE_SELECTION),
+        "herosConnection",
+    );
+
+    assert_eq!(
+        names_and_page(&last_page),
+        (
+            graphql_value!([
+                { "node": { "name": "Superman" } },
+                { "node": { "name": "Green Arrow" } },
+                { "node": { "name": "Robin" } },
+            ]),
+            graphql_value!({ "hasNextPage": false, "hasPreviousPage": true }),
+        )
+    );
+
+    let previous_page = database.field(
+        &format!(
+            "{{ herosConnection(last: 3, before: {:?}) {{ {} }} }}",
+            page_info_cursor(&last_page, "startCursor"),
+            PAGE_SELECTION
+        ),
+        "herosConnection",
+    );
+
+    assert_eq!(
+        names_and_page(&previous_page),
+        (
+            graphql_value!([{ "node": { "name": "Batman" } }]),
+            graphql_value!({ "hasNextPage": true, "hasPreviousPage": false }),
+        )
+    );
+}
+
+#[test]
+fn total_count_ignores_pagination() {
+    let database = Database::connect();
+
+    let connection = database.field(
+        "{ herosConnection(first: 1, query: { name: \"man\" }) { totalCount edges { node { name } } } }",
+        "herosConnection",
+    );
+
+    assert_eq!(
+        connection,
+        graphql_value!({ "totalCount": 2, "edges": [{ "node": { "name": "Batman" } }] })
+    );
+}
+
+#[test]
+fn has_many_connections_are_scoped_to_their_parent() {
+    let database = Database::connect();
+
+    let hero = database.field(
+        "{ hero(id: 1) { enemiesConnection(first: 2) { totalCount edges { node { name } } pageInfo { hasNextPage } } } }",
+        "hero",
+    );
+
+    assert_eq!(
+        hero,
+        graphql_value!({
+            "enemiesConnection": {
+                "totalCount": 4,
+                "edges": [{ "node": { "name": "Joker" } }, { "node": { "name": "Bane" } }],
+                "pageInfo": { "hasNextPage": true },
+            }
+        })
+    );
+}
+
+#[test]
+fn connection_nodes_are_preloaded() {
+    let database = Database::connect();
+
+    // One query for the page and one for every hero's enemies
+    let (_, connections) =
+        database.execute("{ herosConnection(first: 4) { edges { node { enemies { name } } } } }");
+
+    assert_eq!(connections, 2);
+}
+
+#[test]
+fn aliased_connection_fields_are_resolved() {
+    let database = Database::connect();
+
+    // The page and its count share a connection, then every hero's enemy count is loaded at once
+    let (connection, connections) = database.execute(
+        "{ herosConnection(first: 2) { count: totalCount items: edges { hero: node { name enemiesCount } } } }",
+    );
+
+    assert_eq!(connections, 2);
+    assert_eq!(
+        connection,
+        graphql_value!({
+            "herosConnection": {
+                "count": 4,
+                "items": [
+                    { "hero": { "name": "Batman", "enemiesCount": 4 } },
+                    { "hero": { "name": "Superman", "enemiesCount": 3 } },
+                ],
+            }
+        })
+    );
+}
+
+#[test]
+fn has_many_connections_load_every_parent_at_once() {
+    let database = Database::connect();
+
+    // One query for the heros, one for a page of every hero's enemies and one counting them
+    let (heros, connections) = database.execute(
+        "{ heros(ids: [1, 2, 3, 4]) { enemiesConnection(first: 2) { totalCount edges { node { name } } pageInfo { hasNextPage } } } }",
+    );
+
+    assert_eq!(connections, 3);
+    assert_eq!(
+        heros,
+        graphql_value!({
+            "heros": [
+                {
+                    "enemiesConnection": {
+                        "totalCount": 4,
+                        "edges": [{ "node": { "name": "Joker" } }, { "node": { "name": "Bane" } }],
+                        "pageInfo": { "hasNextPage": true },
+                    }
+                },
+                {
+                    "enemiesConnection": {
+                        "totalCount": 3,
+                        "edges": [{ "node": { "name": "Lex Luthor" } }, { "node": { "name": "Zod" } }],
+                        "pageInfo": { "hasNextPage": true },
+                    }
+                },
+                {
+                    "enemiesConnection": {
+                        "totalCount": 2,
+                        "edges": [{ "node": { "name": "Merlyn" } }, { "node": { "name": "Deathstroke" } }],
+                        "pageInfo": { "hasNextPage": false },
+                    }
+                },
+                {
+                    "enemiesConnection": {
+                        "totalCount": 0,
+                        "edges": [],
+                        "pageInfo": { "hasNextPage": false },
+                    }
+                },
+            ]
+        })
+    );
+    // Paginating backwards takes the last of every hero's enemies
+    let heros = database.field(
+        "{ heros(ids: [1, 2]) { enemiesConnection(last: 1) { edges { node { name } } pageInfo { hasPreviousPage } } } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            {
+                "enemiesConnection": {
+                    "edges": [{ "node": { "name": "Penguin" } }],
+                    "pageInfo": { "hasPreviousPage": true },
+                }
+            },
+            {
+                "enemiesConnection": {
+                    "edges": [{ "node": { "name": "Brainiac" } }],
+                    "pageInfo": { "hasPreviousPage": true },
+                }
+            },
+        ])
+    );
+}
@@ -0,0 +1,223 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn filters_combine_with_and() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        r#"{ heros(where: { age: { gt: 30 }, name: { in: ["Batman", "Robin", "Superman"] } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Batman" }, { "name": "Superman" }])
+    );
+}
+
+#[test]
+fn filters_combine_with_or() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        r#"{ heros(where: { _or: [{ age: { lt: 20 } }, { name: { eq: "Superman" } }] }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Superman" }, { "name": "Robin" }])
+    );
+}
+
+#[test]
+fn filters_support_every_operator() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        r#"{ heros(where: { name: { like: "%man", ne: "Superman", isNull: false }, locationId: { eq: 1 } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(heros, graphql_value!([{ "name": "Batman" }]));
+
+    let heros = database.field(
+        "{ heros(where: { age: { isNull: true } }) { name } }",
+        "heros",
+    );
+
+    assert_eq!(heros, graphql_value!([]));
+}
+
+#[test]
+fn filters_apply_within_ids() {
+    let database = Database::connect();
+
+    let enemies = database.field(
+        "{ Enemies(ids: [1, 2, 3, 4, 5, 6], where: { power: { gt: 9000 } }) { name } }",
+        "Enemies",
+    );
+
+    assert_eq!(
+        enemies,
+        graphql_value!([{ "name": "Bane" }, { "name": "Zod" }])
+    );
+}
+
+#[test]
+fn filters_apply_to_connections() {
+    let database = Database::connect();
+
+    let connection = database.field(
+        "{ herosConnection(first: 1, where: { locationId: { eq: 1 } }) { totalCount edges { node { name } } } }",
+        "herosConnection",
+    );
+
+    assert_eq!(
+        connection,
+        graphql_value!({ "totalCount": 2, "edges": [{ "node": { "name": "Batman" } }] })
+    );
+}
+
+#[test]
+fn filters_follow_has_one_relationships() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        r#"{ heros(where: { locationId: { is: { name: { eq: "Gotham" } } } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Batman" }, { "name": "Robin" }])
+    );
+
+    let heros = database.field(
+        r#"{ heros(where: { locationId: { isNot: { name: { eq: "Gotham" } } } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Superman" }, { "name": "Green Arrow" }])
+    );
+}
+
+#[test]
+fn filters_follow_has_many_relationships() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        "{ heros(where: { enemies: { some: { power: { gt: 9000 } } } }) { name } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Batman" }, { "name": "Superman" }, { "name": "Green Arrow" }])
+    );
+
+    let heros = database.field(
+        "{ heros(where: { enemies: { none: { power: { gt: 9000 } } } }) { name } }",
+        "heros",
+    );
+
+    assert_eq!(heros, graphql_value!([{ "name": "Robin" }]));
+
+    // Robin has no enemies, so every one of them matches
+    let heros = database.field(
+        "{ heros(where: { enemies: { every: { power: { gt: 50 } } } }) { name } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Superman" }, { "name": "Green Arrow" }, { "name": "Robin" }])
+    );
+}
+
+#[test]
+fn empty_relationship_filters_match_every_child() {
+    let database = Database::connect();
+
+    let heros = database.field("{ heros(where: { enemies: { every: {} } }) { name } }", "heros");
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Batman" }, { "name": "Superman" }, { "name": "Green Arrow" }, { "name": "Robin" }])
+    );
+
+    // Only heros without enemies have none matching
+    let heros = database.field("{ heros(where: { enemies: { none: {} } }) { name } }", "heros");
+
+    assert_eq!(heros, graphql_value!([{ "name": "Robin" }]));
+}
+
+#[test]
+fn relationship_filters_nest() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        r#"{ heros(where: { enemies: { some: { locationId: { is: { name: { eq: "Metropolis" } } } } } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(heros, graphql_value!([{ "name": "Superman" }]));
+}
+
+#[test]
+fn relationship_filters_apply_the_related_query_modifier() {
+    let database = Database::connect().with_max_enemy_power(9100);
+
+    // Bane and Deathstroke are hidden, leaving Brainiac as the only enemy over 9000 (and under 9100)
+    let heros = database.field(
+        "{ heros(where: { enemies: { some: { power: { gt: 9000 } } } }) { name } }",
+        "heros",
+    );
+
+    assert_eq!(heros, graphql_value!([{ "name": "Superman" }]));
+
+    // Zod is hidden, so every one of Superman's visible enemies is under 9500
+    let heros = database.field(
+        "{ heros(where: { enemies: { every: { power: { lt: 9500 } } } }) { name } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Batman" }, { "name": "Superman" }, { "name": "Green Arrow" }, { "name": "Robin" }])
+    );
+}
+
+#[test]
+fn filters_follow_nullable_has_one_relationships() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        r#"{ heros(where: { mentorId: { is: { name: { eq: "Batman" } } } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(heros, graphql_value!([{ "name": "Robin" }]));
+
+    // Heros without a mentor aren't mentored by Batman either
+    let heros = database.field(
+        r#"{ heros(where: { mentorId: { isNot: { name: { eq: "Batman" } } } }) { name } }"#,
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([{ "name": "Batman" }, { "name": "Superman" }, { "name": "Green Arrow" }])
+    );
+}
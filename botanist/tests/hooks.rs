@@ -0,0 +1,92 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn hooks_rewrite_and_audit_mutations() {
+    let database = Database::connect();
+
+    // Names are trimmed before they're written
+    let created = database.field(
+        r#"mutation { createTeam(input: { name: "  Outsiders  " }) { teamId name } }"#,
+        "createTeam",
+    );
+    let team_id = created
+        .as_object_value()
+        .and_then(|team| team.get_field_value("teamId"))
+        .and_then(|team_id| team_id.as_scalar_value::<i32>())
+        .cloned()
+        .expect("created team has no id");
+
+    assert_eq!(
+        created,
+        graphql_value!({ "teamId": team_id, "name": "Outsiders" })
+    );
+
+    assert_eq!(
+        database.field(
+            &format!(
+                r#"mutation {{ updateTeam(input: {{ teamId: {}, name: " Outsiders II " }}) {{ name }} }}"#,
+                team_id
+            ),
+            "updateTeam"
+        ),
+        graphql_value!({ "name": "Outsiders II" })
+    );
+
+    assert_eq!(
+        database.field(
+            &format!("mutation {{ deleteTeam(id: {}) {{ name }} }}", team_id),
+            "deleteTeam"
+        ),
+        graphql_value!({ "name": "Outsiders II" })
+    );
+
+    assert_eq!(
+        database.audit(),
+        vec![
+            "created Outsiders",
+            "updated Outsiders II",
+            "deleted Outsiders II"
+        ]
+    );
+}
+
+#[test]
+fn hooks_reject_mutations_within_their_transaction() {
+    let database = Database::connect();
+
+    // The duplicate is only noticed after it's inserted, rejecting it rolls the insert back
+    let (created, errors) = database
+        .try_execute(r#"mutation { createTeam(input: { name: " Justice League " }) { name } }"#);
+
+    assert_eq!(created, graphql_value!(None));
+    assert_eq!(errors, vec!["Team names must be unique"]);
+
+    assert_eq!(
+        database.field(
+            r#"{ teams(where: { name: { eq: "Justice League" } }) { teamId } }"#,
+            "teams"
+        ),
+        graphql_value!([{ "teamId": 1 }])
+    );
+
+    let (deleted, errors) = database.try_execute("mutation { deleteTeam(id: 1) { name } }");
+
+    assert_eq!(deleted, graphql_value!(None));
+    assert_eq!(errors, vec!["Teams with members can't be deleted"]);
+
+    assert_eq!(
+        database.field("{ team(id: 1) { name } }", "team"),
+        graphql_value!({ "name": "Justice League" })
+    );
+
+    assert!(database.audit().is_empty());
+}
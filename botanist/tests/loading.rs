@@ -0,0 +1,122 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn children_are_loaded_once_per_request() {
+    let database = Database::connect();
+
+    // Batman and Robin are both in Gotham, neither singular resolver preloads it
+    let (value, connections) = database.execute(
+        "{ batman: hero(id: 1) { locationId { name } } robin: hero(id: 4) { locationId { name } } }",
+    );
+
+    assert_eq!(
+        value,
+        graphql_value!({
+            "batman": { "locationId": { "name": "Gotham" } },
+            "robin": { "locationId": { "name": "Gotham" } },
+        })
+    );
+    assert_eq!(connections, 3);
+}
+
+#[test]
+fn children_of_hand_written_resolvers_are_deduplicated() {
+    let database = Database::connect();
+
+    let (value, connections) =
+        database.execute("{ herosByAge(defer: false) { name locationId { name } } }");
+
+    assert_eq!(
+        value,
+        graphql_value!({
+            "herosByAge": [
+                { "name": "Robin", "locationId": { "name": "Gotham" } },
+                { "name": "Superman", "locationId": { "name": "Metropolis" } },
+                { "name": "Green Arrow", "locationId": { "name": "Star City" } },
+                { "name": "Batman", "locationId": { "name": "Gotham" } },
+            ]
+        })
+    );
+    // One query for the heros, then one for each distinct location
+    assert_eq!(connections, 4);
+}
+
+#[test]
+fn children_of_deferred_models_are_batched() {
+    let database = Database::connect();
+
+    let (value, connections) = database.execute(
+        "{ herosByAge(defer: true) { name locationId { name } mentorId { name } enemiesCount enemies(limit: 1) { name } teams { name } } }",
+    );
+
+    assert_eq!(
+        value,
+        graphql_value!({
+            "herosByAge": [
+                {
+                    "name": "Robin",
+                    "locationId": { "name": "Gotham" },
+                    "mentorId": { "name": "Batman" },
+                    "enemiesCount": 0,
+                    "enemies": [],
+                    "teams": [{ "name": "Bat Family" }, { "name": "Titans" }],
+                },
+                {
+                    "name": "Superman",
+                    "locationId": { "name": "Metropolis" },
+                    "mentorId": None,
+                    "enemiesCount": 3,
+                    "enemies": [{ "name": "Lex Luthor" }],
+                    "teams": [{ "name": "Justice League" }],
+                },
+                {
+                    "name": "Green Arrow",
+                    "locationId": { "name": "Star City" },
+                    "mentorId": None,
+                    "enemiesCount": 2,
+                    "enemies": [{ "name": "Merlyn" }],
+                    "teams": [{ "name": "Justice League" }],
+                },
+                {
+                    "name": "Batman",
+                    "locationId": { "name": "Gotham" },
+                    "mentorId": None,
+                    "enemiesCount": 4,
+                    "enemies": [{ "name": "Joker" }],
+                    "teams": [{ "name": "Justice League" }, { "name": "Bat Family" }],
+                },
+            ]
+        })
+    );
+    // Heros, then one query per relationship (teams are joined through their join table)
+    assert_eq!(connections, 6);
+}
+
+#[test]
+fn children_are_loaded_per_selection() {
+    let database = Database::connect();
+
+    // Robin's mentor is loaded twice, with a different number of enemies each time
+    let value = database
+        .execute(
+            "{ first: hero(id: 4) { mentorId { enemies(limit: 1) { name } } } second: hero(id: 4) { mentorId { enemies(limit: 2) { name } } } }",
+        )
+        .0;
+
+    assert_eq!(
+        value,
+        graphql_value!({
+            "first": { "mentorId": { "enemies": [{ "name": "Joker" }] } },
+            "second": { "mentorId": { "enemies": [{ "name": "Joker" }, { "name": "Bane" }] } },
+        })
+    );
+}
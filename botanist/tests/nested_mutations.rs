@@ -0,0 +1,110 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn creates_related_models_alongside_the_model() {
+    let database = Database::connect();
+
+    // The base is created first, along with its own rooms, then the room referencing it
+    let room = database.field(
+        r#"mutation {
+            createRoom(input: {
+                name: "Batcave",
+                base: { create: { name: "Wayne Manor", rooms: { create: [{ name: "Study" }, { name: "Ballroom" }] } } }
+            }) {
+                name
+                baseId { name rooms { name } }
+            }
+        }"#,
+        "createRoom",
+    );
+
+    assert_eq!(
+        room,
+        graphql_value!({
+            "name": "Batcave",
+            "baseId": {
+                "name": "Wayne Manor",
+                "rooms": [{ "name": "Study" }, { "name": "Ballroom" }, { "name": "Batcave" }],
+            },
+        })
+    );
+}
+
+#[test]
+fn connects_existing_models() {
+    let database = Database::connect();
+
+    let room = database.field(
+        r#"mutation { createRoom(input: { name: "Monitor Womb", base: { connect: 1 } }) { baseId { name } } }"#,
+        "createRoom",
+    );
+
+    assert_eq!(room, graphql_value!({ "baseId": { "name": "Watchtower" } }));
+
+    // A key can still be given as is, but not as well as a nested input
+    let (room, errors) = database.try_execute(
+        r#"mutation { createRoom(input: { name: "Hangar", baseId: 1, base: { connect: 1 } }) { name } }"#,
+    );
+
+    assert_eq!(room, graphql_value!(None));
+    assert_eq!(errors, vec!["Exactly one of baseId or base must be given"]);
+}
+
+#[test]
+fn connects_only_existing_models() {
+    let database = Database::connect().with_hidden_team(1);
+
+    let (room, errors) = database.try_execute(
+        r#"mutation { createRoom(input: { name: "Vault", base: { connect: 99 } }) { name } }"#,
+    );
+
+    assert_eq!(room, graphql_value!(None));
+    assert_eq!(errors, vec!["Base doesn't exist"]);
+
+    // Models hidden by their query modifier can't be connected either
+    let (membership, errors) = database.try_execute(
+        r#"mutation { createTeamMembership(input: { heroId: 3, team: { connect: 1 }, role: "Member" }) { role } }"#,
+    );
+
+    assert_eq!(membership, graphql_value!(None));
+    assert_eq!(errors, vec!["Team doesn't exist"]);
+}
+
+#[test]
+fn nested_writes_roll_back_together() {
+    let database = Database::connect();
+
+    // Rooms can't be unnamed, the second room fails after the base and the first room were inserted
+    let (base, errors) = database.try_execute(
+        r#"mutation {
+            createBase(input: { name: "Fortress of Solitude", rooms: { create: [{ name: "Crystal Chamber" }, { name: "" }] } }) {
+                name
+            }
+        }"#,
+    );
+
+    assert_eq!(base, graphql_value!(None));
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0].contains("rooms_name_check"),
+        "unexpected error: {}",
+        errors[0]
+    );
+
+    assert_eq!(
+        database.field(
+            r#"{ bases(where: { name: { eq: "Fortress of Solitude" } }) { name } }"#,
+            "bases"
+        ),
+        graphql_value!([])
+    );
+}
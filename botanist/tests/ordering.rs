@@ -0,0 +1,87 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn orders_plural_resolvers() {
+    let database = Database::connect();
+
+    let heros = database.field("{ heros(orderBy: [{ age: DESC }]) { name } }", "heros");
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "name": "Batman" },
+            { "name": "Green Arrow" },
+            { "name": "Superman" },
+            { "name": "Robin" },
+        ])
+    );
+}
+
+#[test]
+fn ties_are_broken_by_id() {
+    let database = Database::connect();
+
+    // Batman and Robin share a location
+    let heros = database.field(
+        "{ heros(orderBy: [{ locationId: DESC }], limit: 4) { name } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "name": "Superman" },
+            { "name": "Green Arrow" },
+            { "name": "Batman" },
+            { "name": "Robin" },
+        ])
+    );
+}
+
+#[test]
+fn orders_has_many_fields() {
+    let database = Database::connect();
+
+    let selection = "{ enemies(orderBy: [{ locationId: ASC }], limit: 2) { name } }";
+    let expected =
+        graphql_value!({ "enemies": [{ "name": "Brainiac" }, { "name": "Lex Luthor" }] });
+
+    // Resolved directly
+    assert_eq!(
+        database.field(&format!("{{ hero(id: 2) {} }}", selection), "hero"),
+        expected
+    );
+
+    // Preloaded
+    assert_eq!(
+        database.field(&format!("{{ heros(ids: [2]) {} }}", selection), "heros"),
+        graphql_value!([expected])
+    );
+}
+
+#[test]
+fn has_many_orderings_apply_per_parent() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        "{ heros(ids: [1, 2]) { enemies(orderBy: [{ power: DESC }], limit: 2) { name } } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "enemies": [{ "name": "Bane" }, { "name": "Joker" }] },
+            { "enemies": [{ "name": "Zod" }, { "name": "Brainiac" }] },
+        ])
+    );
+}
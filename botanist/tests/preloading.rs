@@ -0,0 +1,284 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::{graphql_value, DefaultScalarValue, Value};
+
+const HERO_IDS: [i32; 4] = [1, 2, 3, 4];
+
+// Resolves `selection` on every hero through the bulk resolver (which preloads) and through a hand written resolver
+// (which doesn't, so each hero loads its own relationships), the results should be identical
+fn assert_preloaded_matches_resolved(database: &Database, selection: &str) {
+    // Heros are selected by id so the lazily loaded ones can be put in the same order
+    let selection = format!("{{ id {}", selection.trim_start().trim_start_matches('{'));
+
+    let preloaded = database.field(
+        &format!("{{ heros(ids: {:?}) {} }}", HERO_IDS, selection),
+        "heros",
+    );
+
+    let mut resolved = match database.field(&format!("{{ herosByAge(defer: false) {} }}", selection), "herosByAge") {
+        Value::List(heros) => heros,
+        heros => panic!("Expected a list of heros, got {:?}", heros),
+    };
+    resolved.sort_by_key(|hero| {
+        hero.as_object_value()
+            .and_then(|hero| hero.get_field_value("id"))
+            .and_then(|id| id.as_scalar_value::<i32>().cloned())
+    });
+
+    assert_eq!(preloaded, Value::<DefaultScalarValue>::list(resolved));
+}
+
+#[test]
+fn has_many_preloads_in_a_single_query() {
+    let database = Database::connect();
+
+    let (_, connections) = database.execute("{ heros(ids: [1, 2, 3, 4]) { id enemies { id } } }");
+
+    assert_eq!(connections, 2);
+}
+
+#[test]
+fn has_many_preloading_uses_child_arguments() {
+    let database = Database::connect();
+
+    let heros = database.field(
+        "{ heros(ids: [1, 2], limit: 10) { enemies(limit: 2, offset: 1) { name } } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "enemies": [{ "name": "Bane" }, { "name": "Riddler" }] },
+            { "enemies": [{ "name": "Zod" }, { "name": "Brainiac" }] },
+        ])
+    );
+}
+
+#[test]
+fn has_many_preloading_matches_resolvers() {
+    let database = Database::connect();
+
+    assert_preloaded_matches_resolved(&database, "{ id enemies { id name } }");
+    assert_preloaded_matches_resolved(&database, "{ id enemies(limit: 1) { id } }");
+    assert_preloaded_matches_resolved(&database, "{ id enemies(limit: 2, offset: 1) { id } }");
+    assert_preloaded_matches_resolved(&database, "{ id enemies(offset: 3) { id } }");
+    assert_preloaded_matches_resolved(&database, "{ id enemies(limit: 100) { id power } }");
+}
+
+#[test]
+fn has_many_through_preloading_matches_resolvers() {
+    let database = Database::connect();
+
+    assert_preloaded_matches_resolved(&database, "{ id teams { name } }");
+    assert_preloaded_matches_resolved(&database, "{ id teams(limit: 1, offset: 1) { name } }");
+    assert_preloaded_matches_resolved(
+        &database,
+        "{ id teams { name members(limit: 2, offset: 1) { name } } }",
+    );
+}
+
+#[test]
+fn nested_preloading_matches_resolvers() {
+    let database = Database::connect();
+
+    assert_preloaded_matches_resolved(
+        &database,
+        "{ id teams(limit: 1) { members(limit: 3) { name enemies(limit: 1, offset: 1) { name } } } }",
+    );
+}
+
+#[test]
+fn aliased_selections_are_loaded_separately() {
+    let database = Database::connect();
+
+    // Aliases are loaded lazily, but each one in a single query for both heros
+    let (heros, connections) = database.execute(
+        "{ heros(ids: [1, 2]) { first: enemies(limit: 1) { name } all: enemies(limit: 100) { name } } }",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!({
+            "heros": [
+                {
+                    "first": [{ "name": "Joker" }],
+                    "all": [{ "name": "Joker" }, { "name": "Bane" }, { "name": "Riddler" }, { "name": "Penguin" }],
+                },
+                {
+                    "first": [{ "name": "Lex Luthor" }],
+                    "all": [{ "name": "Lex Luthor" }, { "name": "Zod" }, { "name": "Brainiac" }],
+                },
+            ]
+        })
+    );
+    assert_eq!(connections, 3);
+}
+
+#[test]
+fn aliased_preloading_matches_resolvers() {
+    let database = Database::connect();
+
+    assert_preloaded_matches_resolved(
+        &database,
+        "{ first: enemies(limit: 1) { id } rest: enemies(offset: 1) { id } enemies { id } }",
+    );
+    assert_preloaded_matches_resolved(
+        &database,
+        "{ a: teams(limit: 1) { name } b: teams { name x: members(limit: 1) { name } y: members { name } } }",
+    );
+    // Same relation and arguments, only the nested arguments differ
+    assert_preloaded_matches_resolved(
+        &database,
+        "{ a: teams { members(limit: 1) { name } } b: teams { members(limit: 2) { name } } }",
+    );
+}
+
+#[test]
+fn has_many_counts_preload_in_a_single_query() {
+    let database = Database::connect();
+
+    let (heros, connections) =
+        database.execute("{ heros(ids: [1, 2, 3, 4]) { enemiesCount total: enemiesCount } }");
+
+    assert_eq!(connections, 2);
+    assert_eq!(
+        heros,
+        graphql_value!({
+            "heros": [
+                { "enemiesCount": 4, "total": 4 },
+                { "enemiesCount": 3, "total": 3 },
+                { "enemiesCount": 2, "total": 2 },
+                { "enemiesCount": 0, "total": 0 },
+            ]
+        })
+    );
+}
+
+#[test]
+fn has_many_count_preloading_matches_resolvers() {
+    let database = Database::connect();
+
+    assert_preloaded_matches_resolved(&database, "{ enemiesCount }");
+}
+
+#[test]
+fn nullable_has_one_resolves_to_null() {
+    let database = Database::connect();
+
+    // Only Robin has a mentor
+    let heros = database.field("{ heros(ids: [1, 4]) { name mentorId { name } } }", "heros");
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "name": "Batman", "mentorId": None },
+            { "name": "Robin", "mentorId": { "name": "Batman" } },
+        ])
+    );
+
+    assert_preloaded_matches_resolved(&database, "{ id mentorId { id name } }");
+}
+
+#[test]
+fn singular_resolvers_preload_children() {
+    let database = Database::connect();
+
+    let (hero, connections) =
+        database.execute("{ hero(id: 3) { name enemies { name locationId { name } } } }");
+
+    // The hero, its enemies, then every enemy's location at once
+    assert_eq!(connections, 3);
+    assert_eq!(
+        hero,
+        graphql_value!({
+            "hero": {
+                "name": "Green Arrow",
+                "enemies": [
+                    { "name": "Merlyn", "locationId": { "name": "Star City" } },
+                    { "name": "Deathstroke", "locationId": { "name": "Star City" } },
+                ]
+            }
+        })
+    );
+
+    assert_preloaded_matches_resolved(&database, "{ enemies { name locationId { name } } }");
+}
+
+#[test]
+fn mutation_payloads_preload_children() {
+    let database = Database::connect();
+
+    // Green Arrow's age is left as is, other tests rely on it
+    let (hero, connections) = database.execute(
+        "mutation { updateHero(input: { id: 3, age: 38 }) { name enemies { name locationId { name } } } }",
+    );
+
+    assert_eq!(connections, 3);
+    assert_eq!(
+        hero,
+        graphql_value!({
+            "updateHero": {
+                "name": "Green Arrow",
+                "enemies": [
+                    { "name": "Merlyn", "locationId": { "name": "Star City" } },
+                    { "name": "Deathstroke", "locationId": { "name": "Star City" } },
+                ]
+            }
+        })
+    );
+}
+
+#[test]
+fn has_one_preloads_are_matched_by_key() {
+    let database = Database::connect();
+
+    // Locations are stored out of order, so they come back out of order too
+    let (heros, connections) =
+        database.execute("{ heros(ids: [1, 2, 3, 4]) { name locationId { code name } } }");
+
+    assert_eq!(connections, 2);
+    assert_eq!(
+        heros,
+        graphql_value!({
+            "heros": [
+                { "name": "Batman", "locationId": { "code": 1, "name": "Gotham" } },
+                { "name": "Superman", "locationId": { "code": 3, "name": "Metropolis" } },
+                { "name": "Green Arrow", "locationId": { "code": 2, "name": "Star City" } },
+                { "name": "Robin", "locationId": { "code": 1, "name": "Gotham" } },
+            ]
+        })
+    );
+
+    assert_preloaded_matches_resolved(&database, "{ locationId { code name } }");
+}
+
+#[test]
+fn has_one_preloads_skip_missing_rows() {
+    let database = Database::connect();
+
+    // Superman's mentor doesn't exist, Batman mustn't be mistaken for them
+    let heros = database.field(
+        "{ heros(ids: [1, 2, 3, 4]) { name mentorId { name } } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "name": "Batman", "mentorId": None },
+            { "name": "Superman", "mentorId": None },
+            { "name": "Green Arrow", "mentorId": None },
+            { "name": "Robin", "mentorId": { "name": "Batman" } },
+        ])
+    );
+
+    assert_preloaded_matches_resolved(&database, "{ mentorId { name } }");
+}
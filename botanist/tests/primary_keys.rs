@@ -0,0 +1,185 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn resolves_models_by_their_primary_key() {
+    let database = Database::connect();
+
+    assert_eq!(
+        database.field("{ location(id: 2) { code name } }", "location"),
+        graphql_value!({ "code": 2, "name": "Star City" })
+    );
+
+    assert_eq!(
+        database.field("{ teams(ids: [3, 1]) { teamId name } }", "teams"),
+        graphql_value!([
+            { "teamId": 1, "name": "Justice League" },
+            { "teamId": 3, "name": "Titans" },
+        ])
+    );
+}
+
+#[test]
+fn relationships_follow_primary_keys() {
+    let database = Database::connect();
+
+    assert_eq!(
+        database.field(
+            "{ heros(ids: [2, 4]) { locationId { code } teams { teamId } } }",
+            "heros"
+        ),
+        graphql_value!([
+            { "locationId": { "code": 3 }, "teams": [{ "teamId": 1 }] },
+            { "locationId": { "code": 1 }, "teams": [{ "teamId": 2 }, { "teamId": 3 }] },
+        ])
+    );
+
+    assert_eq!(
+        database.field(
+            r#"{ heros(where: { locationId: { is: { code: { eq: 2 } } } }) { name } }"#,
+            "heros"
+        ),
+        graphql_value!([{ "name": "Green Arrow" }])
+    );
+}
+
+#[test]
+fn mutations_follow_primary_keys() {
+    let database = Database::connect();
+
+    let created = database.field(
+        r#"mutation { createLocation(input: { name: "Blüdhaven" }) { code } }"#,
+        "createLocation",
+    );
+    let code = created
+        .as_object_value()
+        .and_then(|object| object.get_field_value("code"))
+        .and_then(|code| code.as_scalar_value::<i32>())
+        .cloned()
+        .expect("missing code");
+
+    assert_eq!(
+        database.field(
+            &format!(
+                r#"mutation {{ updateLocation(input: {{ code: {}, name: "Bludhaven" }}) {{ name }} }}"#,
+                code
+            ),
+            "updateLocation"
+        ),
+        graphql_value!({ "name": "Bludhaven" })
+    );
+
+    assert_eq!(
+        database.field(
+            &format!("mutation {{ deleteLocation(id: {}) {{ name }} }}", code),
+            "deleteLocation"
+        ),
+        graphql_value!({ "name": "Bludhaven" })
+    );
+}
+
+#[test]
+fn resolves_models_by_composite_keys() {
+    let database = Database::connect();
+
+    assert_eq!(
+        database.field(
+            "{ teamMembership(id: { heroId: 4, teamId: 3 }) { role heroId { name } teamId { name } } }",
+            "teamMembership"
+        ),
+        graphql_value!({ "role": "Leader", "heroId": { "name": "Robin" }, "teamId": { "name": "Titans" } })
+    );
+
+    assert_eq!(
+        database.field(
+            "{ teamMemberships(ids: [{ heroId: 4, teamId: 2 }, { heroId: 1, teamId: 2 }, { heroId: 2, teamId: 2 }]) { role } }",
+            "teamMemberships"
+        ),
+        graphql_value!([{ "role": "Mentor" }, { "role": "Sidekick" }])
+    );
+}
+
+#[test]
+fn paginates_composite_keys() {
+    let database = Database::connect();
+
+    let end_cursor = database
+        .field(
+            "{ teamMembershipsConnection(first: 4) { pageInfo { endCursor } } }",
+            "teamMembershipsConnection",
+        )
+        .as_object_value()
+        .and_then(|connection| connection.get_field_value("pageInfo"))
+        .and_then(|page_info| page_info.as_object_value())
+        .and_then(|page_info| page_info.get_field_value("endCursor"))
+        .and_then(|cursor| cursor.as_scalar_value::<String>())
+        .cloned()
+        .expect("missing endCursor");
+
+    // Ordered by hero, then by team
+    assert_eq!(
+        database.field(
+            "{ teamMembershipsConnection(first: 4) { edges { node { role } } } }",
+            "teamMembershipsConnection"
+        ),
+        graphql_value!({
+            "edges": [
+                { "node": { "role": "Founder" } },
+                { "node": { "role": "Mentor" } },
+                { "node": { "role": "Founder" } },
+                { "node": { "role": "Member" } },
+            ]
+        })
+    );
+
+    assert_eq!(
+        database.field(
+            &format!(
+                r#"{{ teamMembershipsConnection(first: 4, after: "{}") {{ edges {{ node {{ role }} }} pageInfo {{ hasNextPage }} }} }}"#,
+                end_cursor
+            ),
+            "teamMembershipsConnection"
+        ),
+        graphql_value!({
+            "edges": [{ "node": { "role": "Sidekick" } }, { "node": { "role": "Leader" } }],
+            "pageInfo": { "hasNextPage": false },
+        })
+    );
+}
+
+#[test]
+fn mutations_follow_composite_keys() {
+    let database = Database::connect();
+
+    assert_eq!(
+        database.field(
+            r#"mutation { createTeamMembership(input: { heroId: 2, teamId: 3, role: "Mentor" }) { role } }"#,
+            "createTeamMembership"
+        ),
+        graphql_value!({ "role": "Mentor" })
+    );
+
+    assert_eq!(
+        database.field(
+            r#"mutation { updateTeamMembership(input: { heroId: 2, teamId: 3, role: "Honorary" }) { role } }"#,
+            "updateTeamMembership"
+        ),
+        graphql_value!({ "role": "Honorary" })
+    );
+
+    assert_eq!(
+        database.field(
+            "mutation { deleteTeamMembership(id: { heroId: 2, teamId: 3 }) { role } }",
+            "deleteTeamMembership"
+        ),
+        graphql_value!({ "role": "Honorary" })
+    );
+}
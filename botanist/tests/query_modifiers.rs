@@ -0,0 +1,112 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn preloaded_relationships_apply_the_related_query_modifier() {
+    let database = Database::connect().with_max_enemy_power(9100);
+
+    // Bane, Zod and Deathstroke are hidden
+    let heros = database.field("{ heros { name enemiesCount enemies { name } } }", "heros");
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            {
+                "name": "Batman",
+                "enemiesCount": 3,
+                "enemies": [{ "name": "Joker" }, { "name": "Riddler" }, { "name": "Penguin" }],
+            },
+            {
+                "name": "Superman",
+                "enemiesCount": 2,
+                "enemies": [{ "name": "Lex Luthor" }, { "name": "Brainiac" }],
+            },
+            { "name": "Green Arrow", "enemiesCount": 1, "enemies": [{ "name": "Merlyn" }] },
+            { "name": "Robin", "enemiesCount": 0, "enemies": [] },
+        ])
+    );
+}
+
+#[test]
+fn loaded_relationships_apply_the_related_query_modifier() {
+    let database = Database::connect().with_max_enemy_power(9100);
+
+    // Hand written resolvers aren't preloaded, their relationships go through the loader
+    let heros = database.field(
+        "{ herosByAge(defer: true) { name enemiesCount enemies { name } } }",
+        "herosByAge",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "name": "Robin", "enemiesCount": 0, "enemies": [] },
+            {
+                "name": "Superman",
+                "enemiesCount": 2,
+                "enemies": [{ "name": "Lex Luthor" }, { "name": "Brainiac" }],
+            },
+            { "name": "Green Arrow", "enemiesCount": 1, "enemies": [{ "name": "Merlyn" }] },
+            {
+                "name": "Batman",
+                "enemiesCount": 3,
+                "enemies": [{ "name": "Joker" }, { "name": "Riddler" }, { "name": "Penguin" }],
+            },
+        ])
+    );
+}
+
+#[test]
+fn relationship_connections_apply_the_related_query_modifier() {
+    let database = Database::connect().with_max_enemy_power(9100);
+
+    let hero = database.field(
+        "{ hero(id: 1) { enemiesConnection(first: 10) { totalCount edges { node { name } } } } }",
+        "hero",
+    );
+
+    assert_eq!(
+        hero,
+        graphql_value!({
+            "enemiesConnection": {
+                "totalCount": 3,
+                "edges": [
+                    { "node": { "name": "Joker" } },
+                    { "node": { "name": "Riddler" } },
+                    { "node": { "name": "Penguin" } },
+                ],
+            }
+        })
+    );
+}
+
+#[test]
+fn has_many_through_pages_apply_the_related_query_modifier() {
+    let database = Database::connect().with_hidden_team(1);
+
+    // The Justice League would be Batman's first team, hidden it's the Bat Family
+    let heros = database.field(
+        "{ heros(ids: [1, 4]) { name teams(limit: 1) { name } } }",
+        "heros",
+    );
+
+    assert_eq!(
+        heros,
+        graphql_value!([
+            { "name": "Batman", "teams": [{ "name": "Bat Family" }] },
+            { "name": "Robin", "teams": [{ "name": "Bat Family" }] },
+        ])
+    );
+
+    let hero = database.field("{ hero(id: 1) { teams(offset: 1) { name } } }", "hero");
+
+    assert_eq!(hero, graphql_value!({ "teams": [] }));
+}
@@ -0,0 +1,148 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::{graphql_value, DefaultScalarValue, Value};
+
+fn id_of(value: &Value<DefaultScalarValue>) -> i32 {
+    value
+        .as_object_value()
+        .and_then(|model| model.get_field_value("id"))
+        .and_then(|id| id.as_scalar_value::<i32>())
+        .cloned()
+        .expect("model has no id")
+}
+
+#[test]
+fn connects_and_disconnects_children() {
+    let database = Database::connect();
+
+    let hall = database.field(
+        r#"mutation {
+            createBase(input: { name: "Hall of Justice", rooms: { create: [{ name: "Lobby" }, { name: "Library" }] } }) {
+                id
+                rooms { id }
+            }
+        }"#,
+        "createBase",
+    );
+    let room_ids = match hall
+        .as_object_value()
+        .and_then(|hall| hall.get_field_value("rooms"))
+    {
+        Some(Value::List(rooms)) => rooms.iter().map(id_of).collect::<Vec<i32>>(),
+        _ => panic!("created base has no rooms"),
+    };
+    let sanctuary = id_of(&database.field(
+        r#"mutation { createBase(input: { name: "Secret Sanctuary" }) { id } }"#,
+        "createBase",
+    ));
+
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ connectBaseRooms(baseId: {}, roomsIds: [{}]) {{ name rooms {{ name }} }} }}",
+                sanctuary, room_ids[0]
+            ),
+            "connectBaseRooms"
+        ),
+        graphql_value!({ "name": "Secret Sanctuary", "rooms": [{ "name": "Lobby" }] })
+    );
+
+    // Every room is connected or none are, the library stays where it was
+    let (base, errors) = database.try_execute(&format!(
+        "mutation {{ connectBaseRooms(baseId: {}, roomsIds: [{}, 0]) {{ name }} }}",
+        sanctuary, room_ids[1]
+    ));
+
+    assert_eq!(base, graphql_value!(None));
+    assert_eq!(errors, vec!["Room doesn't exist"]);
+
+    assert_eq!(
+        database.field(
+            &format!("{{ base(id: {}) {{ rooms {{ name }} }} }}", id_of(&hall)),
+            "base"
+        ),
+        graphql_value!({ "rooms": [{ "name": "Library" }] })
+    );
+
+    // Rooms always belong to a base
+    let (base, errors) = database.try_execute(&format!(
+        "mutation {{ disconnectBaseRooms(baseId: {}, roomsIds: [{}]) {{ name }} }}",
+        sanctuary, room_ids[0]
+    ));
+
+    assert_eq!(base, graphql_value!(None));
+    assert_eq!(
+        errors,
+        vec!["Room's baseId isn't nullable, it can't be disconnected"]
+    );
+}
+
+#[test]
+fn sets_related_models() {
+    let database = Database::connect();
+
+    let base = id_of(&database.field(
+        r#"mutation { createBase(input: { name: "Titans Tower" }) { id } }"#,
+        "createBase",
+    ));
+    let room = id_of(&database.field(
+        r#"mutation { createRoom(input: { name: "Ops", base: { connect: 1 } }) { id } }"#,
+        "createRoom",
+    ));
+
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ setRoomBase(roomId: {}, baseId: {}) {{ name baseId {{ name }} }} }}",
+                room, base
+            ),
+            "setRoomBase"
+        ),
+        graphql_value!({ "name": "Ops", "baseId": { "name": "Titans Tower" } })
+    );
+
+    let (moved, errors) = database.try_execute(&format!(
+        "mutation {{ setRoomBase(roomId: {}, baseId: 0) {{ name }} }}",
+        room
+    ));
+
+    assert_eq!(moved, graphql_value!(None));
+    assert_eq!(errors, vec!["Base doesn't exist"]);
+
+    // Nullable keys can be set to null as well
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ setBaseCommander(baseId: {}, commanderId: 4) {{ commanderId {{ name }} }} }}",
+                base
+            ),
+            "setBaseCommander"
+        ),
+        graphql_value!({ "commanderId": { "name": "Robin" } })
+    );
+
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ setBaseCommander(baseId: {}, commanderId: null) {{ commanderId {{ name }} }} }}",
+                base
+            ),
+            "setBaseCommander"
+        ),
+        graphql_value!({ "commanderId": None })
+    );
+
+    let (set, errors) = database.try_execute(
+        "mutation { setBaseCommander(baseId: 0, commanderId: 1) { name } }",
+    );
+
+    assert_eq!(set, graphql_value!(None));
+    assert_eq!(errors, vec!["Base doesn't exist"]);
+}
@@ -0,0 +1,49 @@
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn omitted_fields_are_left_as_is() {
+    let database = Database::connect();
+
+    let base = database.field(
+        r#"mutation { createBase(input: { name: "Batcave", code: "BC", commanderId: 1 }) { id } }"#,
+        "createBase",
+    );
+    let id = base
+        .as_object_value()
+        .and_then(|base| base.get_field_value("id"))
+        .and_then(|id| id.as_scalar_value::<i32>())
+        .cloned()
+        .expect("base has no id");
+
+    assert_eq!(
+        database.field(
+            &format!(
+                r#"mutation {{ updateBase(input: {{ id: {}, name: "Batcave II" }}) {{ name code commanderId {{ name }} }} }}"#,
+                id
+            ),
+            "updateBase"
+        ),
+        graphql_value!({ "name": "Batcave II", "code": "BC", "commanderId": { "name": "Batman" } })
+    );
+
+    // An explicit null clears the column
+    assert_eq!(
+        database.field(
+            &format!(
+                "mutation {{ updateBase(input: {{ id: {}, code: null, commanderId: null }}) {{ name code commanderId {{ name }} }} }}",
+                id
+            ),
+            "updateBase"
+        ),
+        graphql_value!({ "name": "Batcave II", "code": None, "commanderId": None })
+    );
+}
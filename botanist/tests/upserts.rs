@@ -0,0 +1,106 @@
+// Only meaningful with `--features postgres_upsert,botanist_codegen/postgres_upsert`
+#![cfg(feature = "postgres_upsert")]
+// Diesel 1.4's table! macro predates this lint
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+mod common;
+
+use common::Database;
+use juniper::graphql_value;
+
+#[test]
+fn upserts_on_the_given_columns() {
+    let database = Database::connect();
+
+    let created = database.field(
+        r#"mutation {
+            upsertBase(input: { name: "Hall of Justice", code: "HOJ", rooms: { create: [{ name: "Great Hall" }] } }, conflictOn: [CODE]) {
+                id
+                name
+            }
+        }"#,
+        "upsertBase",
+    );
+    let id = created
+        .as_object_value()
+        .and_then(|base| base.get_field_value("id"))
+        .and_then(|id| id.as_scalar_value::<i32>())
+        .cloned()
+        .expect("upserted base has no id");
+
+    // The second upsert conflicts with the first, updating it (and adding to its rooms) rather than inserting
+    assert_eq!(
+        database.field(
+            r#"mutation {
+                upsertBase(input: { name: "Hall of Justice II", code: "HOJ", rooms: { create: [{ name: "Trophy Room" }] } }, conflictOn: [CODE]) {
+                    id
+                    name
+                    rooms { name }
+                }
+            }"#,
+            "upsertBase"
+        ),
+        graphql_value!({
+            "id": id,
+            "name": "Hall of Justice II",
+            "rooms": [{ "name": "Great Hall" }, { "name": "Trophy Room" }],
+        })
+    );
+
+    let (upserted, errors) = database.try_execute(
+        r#"mutation { upsertBase(input: { name: "Hall of Justice" }, conflictOn: []) { id } }"#,
+    );
+
+    assert_eq!(upserted, graphql_value!(None));
+    assert_eq!(
+        errors,
+        vec!["At least one column to conflict on must be given"]
+    );
+
+    // Conflicting on columns without a unique constraint is left to the database to reject
+    let (upserted, errors) = database.try_execute(
+        r#"mutation { upsertBase(input: { name: "Hall of Justice" }, conflictOn: [NAME]) { id } }"#,
+    );
+
+    assert_eq!(upserted, graphql_value!(None));
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn upserts_on_a_composite_primary_key() {
+    let database = Database::connect();
+
+    let membership = database.field(
+        r#"mutation { upsertTeamMembership(input: { heroId: 3, teamId: 3, role: "Recruit" }, conflictOn: [TEAM_ID, HERO_ID]) { role } }"#,
+        "upsertTeamMembership",
+    );
+
+    assert_eq!(membership, graphql_value!({ "role": "Recruit" }));
+
+    let membership = database.field(
+        r#"mutation { upsertTeamMembership(input: { heroId: 3, teamId: 3, role: "Member" }, conflictOn: [HERO_ID, TEAM_ID, HERO_ID]) { role } }"#,
+        "upsertTeamMembership",
+    );
+
+    assert_eq!(membership, graphql_value!({ "role": "Member" }));
+
+    // Only part of the key can't be a conflict target
+    let (upserted, errors) = database.try_execute(
+        r#"mutation { upsertTeamMembership(input: { heroId: 3, teamId: 3, role: "Member" }, conflictOn: [HERO_ID, ROLE]) { role } }"#,
+    );
+
+    assert_eq!(upserted, graphql_value!(None));
+    assert_eq!(
+        errors,
+        vec!["Only a single column or the whole primary key can be conflicted on"]
+    );
+
+    // Other tests rely on Green Arrow's teams
+    database.field(
+        "mutation { deleteTeamMembership(id: { heroId: 3, teamId: 3 }) { role } }",
+        "deleteTeamMembership",
+    );
+}
@@ -6,17 +6,20 @@ use proc_macro::TokenStream;
 use proc_macro2::{Delimiter, Span, TokenTree};
 
 use syn::{
-    Attribute, Data, DeriveInput, GenericArgument, Ident, Lit, Meta, Path, PathArguments, Type,
+    Attribute, Data, DeriveInput, GenericArgument, Ident, Lit, Meta, NestedMeta, Path,
+    PathArguments, Type,
 };
 
 pub enum TypeRelationship {
     HasMany(Path, Path, Path),
+    HasManyThrough(Path, Path, Path, Path),
     HasOne(Path, Path, Path),
     Field,
 }
 
 enum IterationTypeRelationship {
     HasMany,
+    HasManyThrough,
     HasOne,
     Field,
 }
@@ -26,10 +29,12 @@ pub struct AttributeToken {
     pub arguments: HashMap<String, AttributeToken>,
 }
 
+type InternalAttributeTokenRef = Rc<RefCell<InternalAttributeToken>>;
+
 #[derive(Debug)]
 pub struct InternalAttributeToken {
     pub ident: Ident,
-    pub arguments: HashMap<String, Rc<RefCell<InternalAttributeToken>>>,
+    pub arguments: HashMap<String, InternalAttributeTokenRef>,
 }
 
 impl InternalAttributeToken {
@@ -52,9 +57,7 @@ impl InternalAttributeToken {
     }
 }
 
-pub fn typed_struct_fields_from_ast<'a>(
-    ast: &'a DeriveInput,
-) -> Vec<(&'a Ident, &'a Type, &'a Vec<Attribute>)> {
+pub fn typed_struct_fields_from_ast(ast: &DeriveInput) -> Vec<(&Ident, &Type, &Vec<Attribute>)> {
     match &ast.data {
         Data::Struct(struct_data) => {
             let mut struct_values: Vec<(&Ident, &Type, &Vec<Attribute>)> = Vec::new();
@@ -80,8 +83,7 @@ pub fn tokenized_struct_fields_from_ast(
 ) -> Vec<proc_macro2::TokenStream> {
     typed_struct_fields_from_ast(ast)
         .iter()
-        .map(|(field, ty, _)| tokenizer(field, ty))
-        .filter_map(|maybe_token| maybe_token)
+        .filter_map(|(field, ty, _)| tokenizer(field, ty))
         .collect::<Vec<proc_macro2::TokenStream>>()
 }
 
@@ -98,6 +100,10 @@ pub fn type_relationship(ty: &Type) -> TypeRelationship {
                 relation = IterationTypeRelationship::HasMany;
             }
 
+            if seg.ident == "HasManyThrough" {
+                relation = IterationTypeRelationship::HasManyThrough;
+            }
+
             if let PathArguments::AngleBracketed(generics) = &seg.arguments {
                 return match relation {
                     IterationTypeRelationship::Field => TypeRelationship::Field,
@@ -106,10 +112,8 @@ pub fn type_relationship(ty: &Type) -> TypeRelationship {
                             .args
                             .iter()
                             .map(|generic| {
-                                if let GenericArgument::Type(generic_type) = generic {
-                                    if let Type::Path(path) = generic_type {
-                                        return path.path.clone();
-                                    }
+                                if let GenericArgument::Type(Type::Path(path)) = generic {
+                                    return path.path.clone();
                                 }
 
                                 panic!("Invalid path in HasOne!");
@@ -127,10 +131,8 @@ pub fn type_relationship(ty: &Type) -> TypeRelationship {
                             .args
                             .iter()
                             .map(|generic| {
-                                if let GenericArgument::Type(generic_type) = generic {
-                                    if let Type::Path(path) = generic_type {
-                                        return path.path.clone();
-                                    }
+                                if let GenericArgument::Type(Type::Path(path)) = generic {
+                                    return path.path.clone();
                                 }
 
                                 panic!("Invalid path in HasMany!");
@@ -143,6 +145,31 @@ pub fn type_relationship(ty: &Type) -> TypeRelationship {
 
                         TypeRelationship::HasMany(schema, fk_column, model)
                     }
+                    IterationTypeRelationship::HasManyThrough => {
+                        let mut generics = generics
+                            .args
+                            .iter()
+                            .map(|generic| {
+                                if let GenericArgument::Type(Type::Path(path)) = generic {
+                                    return path.path.clone();
+                                }
+
+                                panic!("Invalid path in HasManyThrough!");
+                            })
+                            .collect::<Vec<Path>>();
+
+                        let model = generics.remove(3);
+                        let remote_fk_column = generics.remove(2);
+                        let local_fk_column = generics.remove(1);
+                        let join_schema = generics.remove(0);
+
+                        TypeRelationship::HasManyThrough(
+                            join_schema,
+                            local_fk_column,
+                            remote_fk_column,
+                            model,
+                        )
+                    }
                 };
             }
         }
@@ -151,10 +178,200 @@ pub fn type_relationship(ty: &Type) -> TypeRelationship {
     TypeRelationship::Field
 }
 
+// Juniper exposes snake_case fields in camelCase, children in a LookAheadSelection are named accordingly
+pub fn graphql_field_name(field: &Ident) -> String {
+    let field = field.to_string();
+    let mut parts = field.trim_start_matches('_').split('_');
+
+    let mut name = parts.next().unwrap_or("").to_string();
+    for part in parts {
+        let mut chars = part.chars();
+
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+
+    name
+}
+
+// Options specified on a field with `#[botanist(option = value)]`
+pub fn field_options(attrs: &[Attribute]) -> HashMap<String, AttributeToken> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("botanist"))
+        .flat_map(|attr| {
+            let options = attr
+                .tokens
+                .clone()
+                .into_iter()
+                .filter_map(|token| match token {
+                    TokenTree::Group(group) => Some(group.stream()),
+                    _ => None,
+                })
+                .collect::<proc_macro2::TokenStream>();
+
+            parse_ident_attributes(options.into()).1
+        })
+        .collect()
+}
+
+pub fn connection_struct(model: &Ident) -> Ident {
+    Ident::new(format!("{}Connection", model).as_ref(), Span::call_site())
+}
+
+pub fn aggregate_struct(model: &Ident) -> Ident {
+    Ident::new(format!("{}Aggregate", model).as_ref(), Span::call_site())
+}
+
+pub fn key_struct(model: &Ident) -> Ident {
+    Ident::new(format!("{}Key", model).as_ref(), Span::call_site())
+}
+
+// Enum of a model's columns, i.e HeroColumn
+pub fn column_enum(model: &Ident) -> Ident {
+    Ident::new(format!("{}Column", model).as_ref(), Span::call_site())
+}
+
+pub fn filter_struct(model: &Ident) -> Ident {
+    Ident::new(format!("{}Filter", model).as_ref(), Span::call_site())
+}
+
+// Filter for a single column of a model, i.e HeroLocationIdFilter
+pub fn column_filter_struct(model: &Ident, field: &Ident) -> Ident {
+    let field = graphql_field_name(field);
+
+    Ident::new(
+        format!(
+            "{}{}{}Filter",
+            model,
+            field[..1].to_uppercase(),
+            &field[1..]
+        )
+        .as_ref(),
+        Span::call_site(),
+    )
+}
+
+// The inner type of an Option, if the type is an Option
+pub fn option_inner_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(generics) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = generics.args.first() {
+                        return Some(inner);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// The key type of a HasOne and whether it's nullable, i.e HasOne<Option<Uuid>, ...> is a nullable Uuid
+pub fn has_one_key_type(relationship_type: &Path) -> (Type, bool) {
+    let ty = Type::Path(syn::TypePath {
+        qself: None,
+        path: relationship_type.clone(),
+    });
+
+    match option_inner_type(&ty) {
+        Some(inner) => (inner.clone(), true),
+        None => (ty, false),
+    }
+}
+
+pub fn is_numeric_type(ty: &Type) -> bool {
+    if let Type::Path(path) = option_inner_type(ty).unwrap_or(ty) {
+        if let Some(segment) = path.path.segments.last() {
+            return ["i16", "i32", "i64", "f32", "f64"]
+                .iter()
+                .any(|numeric| segment.ident == numeric);
+        }
+    }
+
+    false
+}
+
+pub fn is_string_type(ty: &Type) -> bool {
+    if let Type::Path(path) = option_inner_type(ty).unwrap_or(ty) {
+        if let Some(segment) = path.path.segments.last() {
+            return segment.ident == "String";
+        }
+    }
+
+    false
+}
+
+pub fn order_by_struct(model: &Ident) -> Ident {
+    Ident::new(format!("{}OrderBy", model).as_ref(), Span::call_site())
+}
+
+pub fn edge_struct(model: &Ident) -> Ident {
+    Ident::new(format!("{}Edge", model).as_ref(), Span::call_site())
+}
+
+pub fn count_field(field: &Ident) -> Ident {
+    Ident::new(format!("{}_count", field).as_ref(), Span::call_site())
+}
+
+pub fn connection_field(field: &Ident) -> Ident {
+    Ident::new(format!("{}_connection", field).as_ref(), Span::call_site())
+}
+
 pub fn preload_field(field: &Ident) -> Ident {
     Ident::new(format!("{}_preloaded", field).as_ref(), Span::call_site())
 }
 
+pub fn load_field(field: &Ident) -> Ident {
+    Ident::new(format!("load_{}", field).as_ref(), Span::call_site())
+}
+
+// Name a relationship's keys are deferred to the loader under
+pub fn relation(gql_struct_name: &Ident, field: &Ident) -> String {
+    format!("{}.{}", gql_struct_name, field)
+}
+
+// A relationship's name as it appears in its mutations, i.e `location_id` is `Location` and `enemies` is `Enemies`
+pub fn relation_name(field: &Ident) -> String {
+    let field = field.to_string();
+    let field = Ident::new(
+        field.strip_suffix("_id").unwrap_or(&field),
+        Span::call_site(),
+    );
+    upper_camel_case(&field)
+}
+
+// A field's name in UpperCamelCase, i.e `location_id` is `LocationId`
+pub fn upper_camel_case(field: &Ident) -> String {
+    let name = graphql_field_name(field);
+
+    name[..1].to_uppercase() + &name[1..]
+}
+
+// The value taken by the mutations managing a relationship, shared by connecting and disconnecting
+pub fn relation_type(model: &Ident, field: &Ident) -> Ident {
+    Ident::new(
+        format!("{}{}Relation", model, relation_name(field)).as_ref(),
+        Span::call_site(),
+    )
+}
+
+// The argument naming a relationship's value in its mutations, i.e `location_id` is `location_id` and `enemies` is
+// `enemies_ids`
+pub fn relation_argument(field: &Ident) -> Ident {
+    let field = field.to_string();
+
+    if field.ends_with("_id") {
+        return Ident::new(&field, Span::call_site());
+    }
+
+    Ident::new(format!("{}_ids", field).as_ref(), Span::call_site())
+}
+
 pub fn gql_struct(model: &Ident) -> Ident {
     Ident::new(format!("{}GQL", model).as_ref(), Span::call_site())
 }
@@ -196,8 +413,8 @@ pub fn parse_ident_attributes(
 pub fn parse_ident_attributes_from_stream(
     attrs: TokenStream,
 ) -> (
-    impl Iterator<Item = Rc<RefCell<InternalAttributeToken>>>,
-    HashMap<String, Rc<RefCell<InternalAttributeToken>>>,
+    impl Iterator<Item = InternalAttributeTokenRef>,
+    HashMap<String, InternalAttributeTokenRef>,
 ) {
     let idents = proc_macro2::TokenStream::from(attrs).into_iter();
 
@@ -262,6 +479,7 @@ pub fn parse_ident_attributes_from_stream(
                         last_token.borrow_mut().arguments = tup_map;
 
                         named_values.insert(name, last_token);
+                        in_expr = false;
                     }
                 }
 
@@ -307,8 +525,7 @@ pub fn parse_ident_attributes_from_stream(
 pub fn schema_from_struct(ast: &DeriveInput) -> Option<Ident> {
     ast.attrs
         .iter()
-        .filter(|attr| attr.path.is_ident("table_name"))
-        .last()
+        .rfind(|attr| attr.path.is_ident("table_name"))
         .map(|attr| match attr.parse_meta() {
             Ok(meta) => {
                 if let Meta::NameValue(attr_meta) = meta {
@@ -326,6 +543,272 @@ pub fn schema_from_struct(ast: &DeriveInput) -> Option<Ident> {
         .unwrap()
 }
 
+// The primary key of a model, a single column or several for composite keys. Every query on the primary key is
+// generated through here so single and composite keys can be handled alike
+pub struct PrimaryKey {
+    pub columns: Vec<(Ident, Type)>,
+}
+
+impl PrimaryKey {
+    // Uses Diesel's #[primary_key(...)] attribute if the model has one, otherwise the key is just `id`
+    pub fn from_struct(ast: &DeriveInput) -> PrimaryKey {
+        let columns = ast
+            .attrs
+            .iter()
+            .rfind(|attr| attr.path.is_ident("primary_key"))
+            .map(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) => list
+                    .nested
+                    .iter()
+                    .map(|nested| match nested {
+                        NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                            path.get_ident().unwrap().clone()
+                        }
+                        _ => panic!("Invalid column in primary_key!"),
+                    })
+                    .collect::<Vec<Ident>>(),
+                _ => panic!("Invalid primary_key, expected #[primary_key(column, ...)]!"),
+            })
+            .unwrap_or_else(|| vec![Ident::new("id", Span::call_site())]);
+
+        let fields = typed_struct_fields_from_ast(ast);
+
+        PrimaryKey {
+            columns: columns
+                .into_iter()
+                .map(|column| {
+                    let ty = fields
+                        .iter()
+                        .find(|(ident, _, _)| **ident == column)
+                        .map(|(_, ty, _)| match type_relationship(ty) {
+                            TypeRelationship::HasOne(relationship_type, _, _) => {
+                                has_one_key_type(&relationship_type).0
+                            }
+                            _ => (*ty).clone(),
+                        })
+                        .unwrap_or_else(|| {
+                            panic!("{} has no primary key field named {}!", ast.ident, column)
+                        });
+
+                    (column, ty)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_composite(&self) -> bool {
+        self.columns.len() > 1
+    }
+
+    pub fn contains(&self, field: &Ident) -> bool {
+        self.columns.iter().any(|(column, _)| column == field)
+    }
+
+    // The single key column, composite keys don't have one
+    pub fn column(&self) -> &Ident {
+        if self.is_composite() {
+            panic!("Relationships from models with composite primary keys are not supported!");
+        }
+
+        &self.columns[0].0
+    }
+
+    // The type of the key, a tuple for composite keys
+    pub fn ty(&self) -> proc_macro2::TokenStream {
+        let types = self.columns.iter().map(|(_, ty)| ty);
+
+        if self.is_composite() {
+            quote! { (#( #types ),*) }
+        } else {
+            quote! { #( #types )* }
+        }
+    }
+
+    // The key of `model`, which may be a model or a GQL struct
+    pub fn value_of(&self, model: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let values = self
+            .columns
+            .iter()
+            .map(|(column, _)| quote! { #model.#column.clone() });
+
+        if self.is_composite() {
+            quote! { (#( #values ),*) }
+        } else {
+            quote! { #( #values )* }
+        }
+    }
+
+    // Each column of the key, paired with its part of `key`
+    fn parts(
+        &self,
+        schema: &Ident,
+        key: &proc_macro2::TokenStream,
+    ) -> Vec<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(index, (column, _))| {
+                let value = if self.is_composite() {
+                    let index = syn::Index::from(index);
+                    quote! { #key.#index.clone() }
+                } else {
+                    quote! { #key.clone() }
+                };
+
+                (quote! { #schema::#column }, value)
+            })
+            .collect()
+    }
+
+    // An expression matching rows with the given key
+    pub fn matches(
+        &self,
+        schema: &Ident,
+        key: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        self.parts(schema, &key)
+            .into_iter()
+            .map(|(column, value)| quote! { #column.eq(#value) })
+            .reduce(|conditions, condition| quote! { #conditions.and(#condition) })
+            .unwrap()
+    }
+
+    // An expression matching rows with any of the given keys. Diesel has no IN for tuples, so composite keys are
+    // matched by an OR of each key
+    pub fn matches_any(
+        &self,
+        schema: &Ident,
+        context: &Ident,
+        keys: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if !self.is_composite() {
+            let column = self.column();
+
+            return quote! { #schema::#column.eq_any(#keys) };
+        }
+
+        let key_matches = self.matches(schema, quote! { key });
+
+        quote! {
+            {
+                let mut condition: Box<
+                    dyn diesel::expression::BoxableExpression<
+                        #schema::table,
+                        <#context as BotanistContext>::DB,
+                        SqlType = diesel::sql_types::Bool
+                    >
+                > = Box::new(diesel::dsl::sql::<diesel::sql_types::Bool>("FALSE"));
+
+                for key in #keys.iter() {
+                    condition = Box::new(condition.or(#key_matches));
+                }
+
+                condition
+            }
+        }
+    }
+
+    // Orders by the key
+    pub fn order(&self, schema: &Ident, descending: bool) -> proc_macro2::TokenStream {
+        let orders = self.orders(schema, descending);
+
+        if self.is_composite() {
+            quote! { (#( #orders ),*) }
+        } else {
+            quote! { #( #orders )* }
+        }
+    }
+
+    // Orders by the key, column by column
+    pub fn orders(&self, schema: &Ident, descending: bool) -> Vec<proc_macro2::TokenStream> {
+        self.columns
+            .iter()
+            .map(|(column, _)| {
+                if descending {
+                    quote! { #schema::#column.desc() }
+                } else {
+                    quote! { #schema::#column.asc() }
+                }
+            })
+            .collect()
+    }
+
+    // An expression matching rows that come after (or before) the given key when ordered by the key
+    pub fn follows(
+        &self,
+        schema: &Ident,
+        key: proc_macro2::TokenStream,
+        descending: bool,
+    ) -> proc_macro2::TokenStream {
+        self.parts(schema, &key)
+            .into_iter()
+            .rev()
+            .fold(None, |following, (column, value)| {
+                let beyond = if descending {
+                    quote! { #column.lt(#value) }
+                } else {
+                    quote! { #column.gt(#value) }
+                };
+
+                Some(match following {
+                    Some(following) => quote! { #beyond.or(#column.eq(#value).and(#following)) },
+                    None => beyond,
+                })
+            })
+            .unwrap()
+    }
+
+    pub fn encode_cursor(&self, model: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let parts = self
+            .columns
+            .iter()
+            .map(|(column, _)| quote! { botanist::connection::encode_cursor(&#model.#column) });
+
+        if self.is_composite() {
+            quote! { botanist::connection::join_cursor(&[#( #parts ),*]) }
+        } else {
+            quote! { #( #parts )* }
+        }
+    }
+
+    pub fn decode_cursor(&self, cursor: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if !self.is_composite() {
+            let ty = self.ty();
+
+            return quote! { botanist::connection::decode_cursor::<#ty>(&#cursor) };
+        }
+
+        let count = self.columns.len();
+        let parts = self.columns.iter().enumerate().map(|(index, (_, ty))| {
+            quote! { botanist::connection::decode_cursor::<#ty>(parts[#index])? }
+        });
+
+        quote! {
+            botanist::connection::split_cursor(&#cursor, #count).and_then(|parts| Ok((#( #parts ),*)))
+        }
+    }
+}
+
+// Wraps a resolver that hits the database. In async mode it becomes an async fn, handing its body to the context's
+// spawn_blocking hook, otherwise it's left as is
+pub fn blocking_resolver(resolver: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "async") {
+        return resolver;
+    }
+
+    let mut method: syn::ImplItemMethod = syn::parse2(resolver).expect("Invalid resolver!");
+    let block = &method.block;
+
+    method.block = syn::parse_quote! {
+        {
+            botanist::AsyncContext::spawn_blocking(context, Box::new(move || #block)).await
+        }
+    };
+    method.sig.asyncness = Some(Default::default());
+
+    quote! { #method }
+}
+
 pub fn lower_first(input: &str) -> String {
     let first = input
         .chars()
@@ -335,3 +818,18 @@ pub fn lower_first(input: &str) -> String {
         .to_lowercase();
     first + &input[1..]
 }
+
+// The snake_case form of a model's name, i.e `TeamMembership` is `team_membership`
+pub fn snake_case(input: &str) -> String {
+    let mut snake = String::new();
+
+    for (index, character) in input.chars().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            snake.push('_');
+        }
+
+        snake.extend(character.to_lowercase());
+    }
+
+    snake
+}
@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
+use std::collections::HashMap;
 use syn::{DeriveInput, ItemImpl, Type};
 
 use crate::common;
@@ -14,14 +15,26 @@ pub fn botanist_mutation(attrs: TokenStream, input: TokenStream) -> TokenStream
             .get("Context")
             .expect("a context must be specified")
             .ident;
-        let primary_key_ty = &params
-            .get("PrimaryKey")
-            .expect("a primary key type must be specified")
-            .ident;
 
-        let mutations = mutation_models.map(|token| {
+        let mutation_models = mutation_models.collect::<Vec<common::AttributeToken>>();
+
+        // Upserts rely on `ON CONFLICT`, which only exists with the postgres_upsert feature
+        if !cfg!(feature = "postgres_upsert") {
+            let upsert = mutation_models.iter().find_map(|token| upsert_argument(token).map(|upsert| (token, upsert)));
+
+            if let Some((token, upsert)) = upsert {
+                return syn::Error::new_spanned(
+                    &upsert.ident,
+                    format!("Upserting {} requires the postgres_upsert feature", token.ident),
+                )
+                .to_compile_error()
+                .into();
+            }
+        }
+
+        let mutations = mutation_models.into_iter().map(|token| {
             let model = &token.ident;
-            let graphql_type = common::gql_struct(&model);
+            let graphql_type = common::gql_struct(model);
 
             let create_mutation_struct = Ident::new(format!("Create{}Input", model).as_ref(), Span::call_site());
             let create_mutation = Ident::new(format!("create{}", model).as_ref(), Span::call_site());
@@ -31,19 +44,142 @@ pub fn botanist_mutation(attrs: TokenStream, input: TokenStream) -> TokenStream
 
             let delete_mutation = Ident::new(format!("delete{}", model).as_ref(), Span::call_site());
 
-            quote! {
-                pub fn #create_mutation(context: &#context_ty, input: #create_mutation_struct) -> juniper::FieldResult<#graphql_type> {
-                    #create_mutation_struct::create(context, input)
+            let create_resolver = common::blocking_resolver(quote! {
+                pub fn #create_mutation(context: &#context_ty, executor: &Executor, input: #create_mutation_struct) -> juniper::FieldResult<#graphql_type> {
+                    #create_mutation_struct::create(context, executor, input)
+                }
+            });
+
+            let update_resolver = common::blocking_resolver(quote! {
+                pub fn #update_mutation(context: &#context_ty, executor: &Executor, input: #update_mutation_struct) -> juniper::FieldResult<#graphql_type> {
+                    #update_mutation_struct::update(context, executor, input)
+                }
+            });
+
+            let delete_resolver = common::blocking_resolver(quote! {
+                pub fn #delete_mutation(
+                    context: &#context_ty,
+                    executor: &Executor,
+                    id: <#graphql_type as botanist::internal::__internal__Keyable>::Key
+                ) -> juniper::FieldResult<#graphql_type> {
+                    #graphql_type::delete(context, executor, id.into())
                 }
+            });
+
+            // Batches are opt-in (`batch = true`), named after the model's plural like its query
+            let batch_resolvers = if token.arguments.get("batch").map(|token| token.ident == "true").unwrap_or(false) {
+                let plural = token.arguments.get("plural")
+                    .map(|token| token.ident.to_string())
+                    .unwrap_or(format!("{}s", model));
+
+                let create_all_mutation = Ident::new(format!("create{}", plural).as_ref(), Span::call_site());
+                let update_all_mutation = Ident::new(format!("update{}", plural).as_ref(), Span::call_site());
+                let delete_all_mutation = Ident::new(format!("delete{}", plural).as_ref(), Span::call_site());
+
+                let create_all_resolver = common::blocking_resolver(quote! {
+                    pub fn #create_all_mutation(context: &#context_ty, executor: &Executor, inputs: Vec<#create_mutation_struct>) -> juniper::FieldResult<Vec<#graphql_type>> {
+                        #create_mutation_struct::create_all(context, executor, inputs)
+                    }
+                });
+
+                let update_all_resolver = common::blocking_resolver(quote! {
+                    pub fn #update_all_mutation(context: &#context_ty, executor: &Executor, inputs: Vec<#update_mutation_struct>) -> juniper::FieldResult<Vec<#graphql_type>> {
+                        #update_mutation_struct::update_all(context, executor, inputs)
+                    }
+                });
+
+                let delete_all_resolver = common::blocking_resolver(quote! {
+                    pub fn #delete_all_mutation(
+                        context: &#context_ty,
+                        executor: &Executor,
+                        ids: Vec<<#graphql_type as botanist::internal::__internal__Keyable>::Key>
+                    ) -> juniper::FieldResult<Vec<#graphql_type>> {
+                        #graphql_type::delete_all(context, executor, ids.into_iter().map(Into::into).collect())
+                    }
+                });
 
-                pub fn #update_mutation(context: &#context_ty, input: #update_mutation_struct) -> juniper::FieldResult<#graphql_type> {
-                    #update_mutation_struct::update(context, input)
+                quote! {
+                    #create_all_resolver
+                    #update_all_resolver
+                    #delete_all_resolver
                 }
+            } else {
+                quote! {}
+            };
+
+            // Upserts are opt-in too (`upsert = true`), and need the postgres_upsert feature
+            let upsert_resolver = if upsert_argument(&token).is_some() {
+                let upsert_mutation = Ident::new(format!("upsert{}", model).as_ref(), Span::call_site());
+
+                // Only models with a create input implement the trait, models that can only be connected can't be upserted
+                common::blocking_resolver(quote! {
+                    pub fn #upsert_mutation(
+                        context: &#context_ty,
+                        executor: &Executor,
+                        input: <#graphql_type as botanist::internal::__internal__Upsert<#context_ty>>::Input,
+                        conflict_on: Vec<<#graphql_type as botanist::internal::__internal__Upsert<#context_ty>>::Column>
+                    ) -> juniper::FieldResult<#graphql_type> {
+                        <#graphql_type as botanist::internal::__internal__Upsert<#context_ty>>::upsert(context, executor, input, conflict_on)
+                    }
+                })
+            } else {
+                quote! {}
+            };
+
+            // Relationships are opt-in, i.e `Hero(relations = (enemies, location_id))`. HasOne keys (`<name>_id`) are
+            // set, HasMany children are connected and disconnected
+            let mut relations = token.arguments.get("relations")
+                .map(|token| token.arguments.keys().cloned().collect::<Vec<String>>())
+                .unwrap_or_default();
+            relations.sort();
+
+            // i.e `connectHeroEnemies(heroId, enemiesIds)` and `setHeroLocation(heroId, locationId)`
+            let key_argument = Ident::new(
+                format!("{}_id", common::snake_case(&model.to_string())).as_ref(),
+                Span::call_site()
+            );
+
+            let mut relation_resolvers = Vec::new();
+            for relation in relations.iter() {
+                let field = Ident::new(relation, Span::call_site());
+                let relation_type = common::relation_type(model, &field);
+                let relation_argument = common::relation_argument(&field);
+                let relation_name = common::relation_name(&field);
 
-                pub fn #delete_mutation(context: &#context_ty, id: #primary_key_ty) -> juniper::FieldResult<#graphql_type> {
-                    #graphql_type::delete(context, id)
+                let operations = if relation.ends_with("_id") {
+                    vec!["set"]
+                } else {
+                    vec!["connect", "disconnect"]
+                };
+
+                for operation in operations {
+                    let relation_mutation = Ident::new(
+                        format!("{}{}{}", operation, model, relation_name).as_ref(),
+                        Span::call_site()
+                    );
+                    let operation = Ident::new(format!("{}_{}", operation, field).as_ref(), Span::call_site());
+
+                    relation_resolvers.push(common::blocking_resolver(quote! {
+                        pub fn #relation_mutation(
+                            context: &#context_ty,
+                            executor: &Executor,
+                            #key_argument: <#graphql_type as botanist::internal::__internal__Keyable>::Key,
+                            #relation_argument: #relation_type
+                        ) -> juniper::FieldResult<#graphql_type> {
+                            #graphql_type::#operation(context, executor, #key_argument, #relation_argument)
+                        }
+                    }));
                 }
             }
+
+            quote! {
+                #create_resolver
+                #update_resolver
+                #delete_resolver
+                #batch_resolvers
+                #upsert_resolver
+                #( #relation_resolvers )*
+            }
         })
         .collect::<Vec<proc_macro2::TokenStream>>();
 
@@ -63,65 +199,722 @@ pub fn botanist_mutation(attrs: TokenStream, input: TokenStream) -> TokenStream
     panic!("Attempted to implement botanist_mutation on invalid mutation type!");
 }
 
+// The `upsert = true` argument of a model, if it opted in
+fn upsert_argument(token: &common::AttributeToken) -> Option<&common::AttributeToken> {
+    token.arguments.get("upsert").filter(|upsert| upsert.ident == "true")
+}
+
+// A field of the create input, as written by the client
+enum CreateField<'a> {
+    // A column, written as is
+    Column(&'a Ident, proc_macro2::TokenStream),
+    // A HasOne key (`<name>_id`), given either as is or through a nested `<name>` input connecting or creating the model
+    Related {
+        field: &'a Ident,
+        nested: Ident,
+        key_ty: proc_macro2::TokenStream,
+        nullable: bool,
+        graphql_type: Ident,
+    },
+    // A HasMany, whose children are created alongside the model
+    Children {
+        field: &'a Ident,
+        forign_key: syn::Path,
+        graphql_type: Ident,
+    },
+}
+
+impl<'a> CreateField<'a> {
+    fn is(&self, ident: &Ident) -> bool {
+        match self {
+            CreateField::Column(field, _) => *field == ident,
+            CreateField::Related { field, .. } => *field == ident,
+            CreateField::Children { .. } => false,
+        }
+    }
+
+    fn idents(&self) -> Vec<&Ident> {
+        match self {
+            CreateField::Column(field, _) => vec![field],
+            CreateField::Related { field, nested, .. } => vec![field, nested],
+            CreateField::Children { field, .. } => vec![field],
+        }
+    }
+
+    fn declaration(&self, context: &Ident) -> proc_macro2::TokenStream {
+        match self {
+            CreateField::Column(field, ty) => quote! {
+                pub #field: #ty
+            },
+            CreateField::Related {
+                field,
+                nested,
+                key_ty,
+                graphql_type,
+                ..
+            } => quote! {
+                pub #field: Option<#key_ty>,
+                pub #nested: Option<<#graphql_type as __internal__ConnectOrCreate<#context>>::Input>
+            },
+            CreateField::Children {
+                field,
+                forign_key,
+                graphql_type,
+            } => quote! {
+                pub #field: Option<<#graphql_type as __internal__CreateWithout<#forign_key, #context>>::Input>
+            },
+        }
+    }
+}
+
+// The create input's fields. HasOne keys and HasMany forign keys are recognized by name (`<name>_id`), nested writes
+// aren't offered for relationships named otherwise
+fn create_fields<'a>(
+    ast: &'a DeriveInput,
+    primary_key: &common::PrimaryKey,
+) -> Vec<CreateField<'a>> {
+    let fields = common::typed_struct_fields_from_ast(ast);
+    let field_names = fields
+        .iter()
+        .map(|(field, _, _)| field.to_string())
+        .collect::<Vec<String>>();
+
+    fields
+        .iter()
+        .filter_map(|(field, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasOne(relationship_type, _, model) => {
+                let nested = field.to_string().strip_suffix("_id").map(str::to_string);
+
+                match nested.filter(|nested| !field_names.contains(nested)) {
+                    Some(nested) => {
+                        let (key_ty, nullable) = common::has_one_key_type(&relationship_type);
+
+                        Some(CreateField::Related {
+                            field,
+                            nested: Ident::new(&nested, Span::call_site()),
+                            key_ty: quote! { #key_ty },
+                            nullable,
+                            graphql_type: common::gql_struct_from_model(&model),
+                        })
+                    }
+                    None => Some(CreateField::Column(field, quote! { #relationship_type })),
+                }
+            }
+            common::TypeRelationship::HasMany(_, forign_key, model) => {
+                if forign_key
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string().ends_with("_id"))
+                    .unwrap_or(false)
+                {
+                    Some(CreateField::Children {
+                        field,
+                        forign_key,
+                        graphql_type: common::gql_struct_from_model(&model),
+                    })
+                } else {
+                    None
+                }
+            }
+            common::TypeRelationship::HasManyThrough(_, _, _, _) => None,
+            common::TypeRelationship::Field => {
+                // Single primary keys are left to the database, composite ones are usually forign keys
+                if primary_key.contains(field) && !primary_key.is_composite() {
+                    None
+                } else {
+                    Some(CreateField::Column(field, quote! { #ty }))
+                }
+            }
+        })
+        .collect()
+}
+
 pub fn generate_create_mutation(
     ast: &DeriveInput,
     struct_name: &Ident,
     schema: &Ident,
     gql_struct_name: &Ident,
     context: &Ident,
-) -> Option<proc_macro2::TokenStream> {
+    primary_key: &common::PrimaryKey,
+    params: &HashMap<String, common::AttributeToken>,
+) -> proc_macro2::TokenStream {
     let create_mutation_struct = Ident::new(
         format!("Create{}Input", struct_name).as_ref(),
         Span::call_site(),
     );
     let create_mutation_struct_name = format!("New{}", struct_name);
+    let insertable_struct = Ident::new(
+        format!("Insertable{}", struct_name).as_ref(),
+        Span::call_site(),
+    );
 
     let schema_str = schema.to_string();
+    let fields = create_fields(ast, primary_key);
 
-    let tokenized_create_mutation_fields = common::tokenized_struct_fields_from_ast(
-        &ast,
-        |ident, ty| match common::type_relationship(ty) {
-            common::TypeRelationship::HasMany(_, _, _) => None,
-            common::TypeRelationship::HasOne(relationship_type, _, _) => Some(quote! {
-                pub #ident: #relationship_type
-            }),
-            common::TypeRelationship::Field => {
-                if ident == "id" {
-                    None
-                } else {
-                    Some(quote! {
-                        pub #ident: #ty
-                    })
+    // Models with composite keys can't be connected, nor have children
+    let connect_or_create = |create: Option<proc_macro2::TokenStream>| {
+        if primary_key.is_composite() {
+            return quote! {};
+        }
+
+        let connect_or_create_struct = Ident::new(
+            format!("ConnectOrCreate{}Input", struct_name).as_ref(),
+            Span::call_site(),
+        );
+        let connect_or_create_struct_name = format!("ConnectOrCreate{}", struct_name);
+        let id_ty = primary_key.ty();
+        let created_key = primary_key.value_of(quote! { created });
+        let key_matches = primary_key.matches(schema, quote! { key });
+        let missing = format!("{} doesn't exist", struct_name);
+
+        let (create_field, create_input, create_arm) = match create {
+            Some(create) => (
+                quote! { pub create: Option<Box<#create>>, },
+                quote! { input.create },
+                quote! {
+                    (None, Some(create)) => {
+                        let created = create.insert(context, connection)?;
+
+                        Ok(#created_key)
+                    },
+                },
+            ),
+            None => (quote! {}, quote! { None::<()> }, quote! {}),
+        };
+
+        quote! {
+            #[derive(juniper::GraphQLInputObject)]
+            #[graphql(name=#connect_or_create_struct_name)]
+            pub struct #connect_or_create_struct {
+                pub connect: Option<#id_ty>,
+                #create_field
+            }
+
+            impl __internal__ConnectOrCreate<#context> for #gql_struct_name {
+                type Input = #connect_or_create_struct;
+                type Key = #id_ty;
+
+                fn connect_or_create(
+                    input: #connect_or_create_struct,
+                    context: &#context,
+                    connection: &<#context as BotanistContext>::Connection
+                ) -> juniper::FieldResult<#id_ty> {
+                    match (input.connect, #create_input) {
+                        // Connected models must exist, and be visible through the query modifier
+                        (Some(key), None) => {
+                            let found = #gql_struct_name::scoped_query(context)?
+                                .filter(#key_matches)
+                                .count()
+                                .get_result::<i64>(connection)?;
+
+                            if found == 0 {
+                                return Err(juniper::FieldError::new(#missing, juniper::Value::null()));
+                            }
+
+                            Ok(key)
+                        },
+                        #create_arm
+                        _ => Err(juniper::FieldError::new(
+                            "Exactly one of connect or create must be given",
+                            juniper::Value::null()
+                        ))
+                    }
+                }
+            }
+        }
+    };
+
+    // Nothing to insert, the model can only be connected
+    if !fields
+        .iter()
+        .any(|field| !matches!(field, CreateField::Children { .. }))
+    {
+        return connect_or_create(None);
+    }
+
+    let connect_or_create = connect_or_create(Some(quote! { #create_mutation_struct }));
+    let declarations = fields.iter().map(|field| field.declaration(context));
+    let destructured = fields.iter().flat_map(|field| field.idents());
+
+    // Insertable columns, after the nested HasOne inputs have been resolved to keys
+    let columns = fields.iter().filter_map(|field| match field {
+        CreateField::Column(field, ty) => Some(quote! { #field: #ty }),
+        CreateField::Related {
+            field,
+            key_ty,
+            nullable,
+            ..
+        } => Some(if *nullable {
+            quote! { #field: Option<#key_ty> }
+        } else {
+            quote! { #field: #key_ty }
+        }),
+        CreateField::Children { .. } => None,
+    });
+    let column_names = fields
+        .iter()
+        .filter_map(|field| match field {
+            CreateField::Column(field, _) | CreateField::Related { field, .. } => Some(*field),
+            CreateField::Children { .. } => None,
+        })
+        .collect::<Vec<&Ident>>();
+
+    let connections = fields.iter().filter_map(|field| match field {
+        CreateField::Related {
+            field,
+            nested,
+            nullable,
+            graphql_type,
+            ..
+        } => {
+            let connected = quote! {
+                <#graphql_type as __internal__ConnectOrCreate<#context>>::connect_or_create(#nested, context, connection)?
+            };
+
+            Some(if *nullable {
+                let error = format!(
+                    "Only one of {} or {} may be given",
+                    common::graphql_field_name(field),
+                    common::graphql_field_name(nested)
+                );
+
+                quote! {
+                    let #field = match (#field, #nested) {
+                        (key, None) => key,
+                        (None, Some(#nested)) => Some(#connected),
+                        _ => return Err(juniper::FieldError::new(#error, juniper::Value::null())),
+                    };
+                }
+            } else {
+                let error = format!(
+                    "Exactly one of {} or {} must be given",
+                    common::graphql_field_name(field),
+                    common::graphql_field_name(nested)
+                );
+
+                quote! {
+                    let #field = match (#field, #nested) {
+                        (Some(key), None) => key,
+                        (None, Some(#nested)) => #connected,
+                        _ => return Err(juniper::FieldError::new(#error, juniper::Value::null())),
+                    };
+                }
+            })
+        }
+        _ => None,
+    });
+
+    let children = fields.iter().filter_map(|field| match field {
+        CreateField::Children {
+            field,
+            forign_key,
+            graphql_type,
+        } => {
+            let key = primary_key.column();
+
+            Some(quote! {
+                if let Some(#field) = #field {
+                    <#graphql_type as __internal__CreateWithout<#forign_key, #context>>::create_without(
+                        #field,
+                        create_result.#key.clone(),
+                        context,
+                        connection
+                    )?;
+                }
+            })
+        }
+        _ => None,
+    })
+    .collect::<Vec<proc_macro2::TokenStream>>();
+    let (children_fields, children_types): (Vec<&Ident>, Vec<proc_macro2::TokenStream>) = fields
+        .iter()
+        .filter_map(|field| match field {
+            CreateField::Children {
+                field,
+                forign_key,
+                graphql_type,
+            } => Some((
+                *field,
+                quote! { Option<<#graphql_type as __internal__CreateWithout<#forign_key, #context>>::Input> },
+            )),
+            _ => None,
+        })
+        .unzip();
+    let has_children = if children.is_empty() {
+        quote! { false }
+    } else {
+        quote! { nested.iter().any(|( #( #children_fields, )* )| #( #children_fields.is_some() )||*) }
+    };
+
+    // Inputs for creating the model as a child of another, the forign key being filled in by the parent
+    let create_without = fields.iter().filter_map(|without| {
+        let (without_field, value_ty, value) = match without {
+            CreateField::Related { field, key_ty, .. } => {
+                (*field, quote! { #key_ty }, quote! { Some(value.clone()) })
+            }
+            CreateField::Column(field, ty) if field.to_string().ends_with("_id") => {
+                let ty: Type = syn::parse2(ty.clone()).unwrap();
+
+                match common::option_inner_type(&ty) {
+                    Some(inner) => (*field, quote! { #inner }, quote! { Some(value.clone()) }),
+                    None => (*field, quote! { #ty }, quote! { value.clone() }),
+                }
+            }
+            _ => return None,
+        };
+
+        let remaining = fields
+            .iter()
+            .filter(|field| !field.is(without_field))
+            .collect::<Vec<&CreateField>>();
+
+        // GraphQL input objects need at least one field
+        if remaining.is_empty() {
+            return None;
+        }
+
+        let without_name = common::upper_camel_case(without_field);
+        let without_struct = Ident::new(
+            format!("Create{}Without{}Input", struct_name, without_name).as_ref(),
+            Span::call_site(),
+        );
+        let without_struct_name = format!("New{}Without{}", struct_name, without_name);
+        let many_struct = Ident::new(
+            format!("CreateMany{}Without{}Input", struct_name, without_name).as_ref(),
+            Span::call_site(),
+        );
+        let many_struct_name = format!("CreateMany{}Without{}", struct_name, without_name);
+
+        let declarations = remaining.iter().map(|field| field.declaration(context));
+        let remaining_fields = remaining
+            .iter()
+            .flat_map(|field| field.idents())
+            .collect::<Vec<&Ident>>();
+        let nested_field = match without {
+            CreateField::Related { nested, .. } => Some(quote! { #nested: None, }),
+            _ => None,
+        };
+
+        Some(quote! {
+            #[derive(juniper::GraphQLInputObject)]
+            #[graphql(name=#without_struct_name)]
+            pub struct #without_struct {
+                #( #declarations, )*
+            }
+
+            #[derive(juniper::GraphQLInputObject)]
+            #[graphql(name=#many_struct_name)]
+            pub struct #many_struct {
+                pub create: Vec<#without_struct>,
+            }
+
+            impl __internal__CreateWithout<#schema::#without_field, #context> for #gql_struct_name {
+                type Input = #many_struct;
+                type Value = #value_ty;
+
+                fn create_without(
+                    input: #many_struct,
+                    value: #value_ty,
+                    context: &#context,
+                    connection: &<#context as BotanistContext>::Connection
+                ) -> juniper::FieldResult<()> {
+                    for child in input.create.into_iter() {
+                        let #without_struct { #( #remaining_fields ),* } = child;
+
+                        #create_mutation_struct {
+                            #( #remaining_fields, )*
+                            #without_field: #value,
+                            #nested_field
+                        }
+                        .insert(context, connection)?;
+                    }
+
+                    Ok(())
                 }
             }
+        })
+    });
+
+    let before_create = hook(
+        params,
+        "BeforeCreate",
+        quote! {
+            let self_model = <#struct_name as botanist::BeforeCreate<#create_mutation_struct, #context>>::before_create(
+                self_model,
+                context,
+                connection
+            )?;
+        },
+    );
+    let after_create = hook(
+        params,
+        "AfterCreate",
+        quote! {
+            <#struct_name as botanist::AfterCreate<#context>>::after_create(create_result, context, connection)?;
         },
     );
 
-    if tokenized_create_mutation_fields.is_empty() {
-        None
+    // Children and the after hook follow each insert, there's nothing to do for models with neither
+    let after_insert = if children.is_empty() && after_create.is_empty() {
+        quote! {}
     } else {
-        Some(quote! {
-            #[derive(juniper::GraphQLInputObject, Insertable)]
-            #[graphql(name=#create_mutation_struct_name)]
-            #[table_name = #schema_str]
-            pub struct #create_mutation_struct {
-                #( #tokenized_create_mutation_fields, )*
+        quote! {
+            for (create_result, ( #( #children_fields, )* )) in create_results.iter().zip(nested) {
+                #( #children )*
+
+                #after_create
+            }
+        }
+    };
+
+    // Upserts are Postgres only (Diesel has no ON CONFLICT for other backends), and treated as creates by hooks and
+    // nested writes. A conflict updates every column the insert would have written
+    let upsert = if cfg!(feature = "postgres_upsert") {
+        let column_enum = common::column_enum(struct_name);
+        let column_enum_name = column_enum.to_string();
+        let table_columns = common::typed_struct_fields_from_ast(ast)
+            .into_iter()
+            .filter(|(_, ty, _)| {
+                matches!(
+                    common::type_relationship(ty),
+                    common::TypeRelationship::HasOne(_, _, _) | common::TypeRelationship::Field
+                )
+            })
+            .map(|(field, _, _)| field)
+            .collect::<Vec<&Ident>>();
+        let variants = table_columns
+            .iter()
+            .map(|field| Ident::new(&common::upper_camel_case(field), Span::call_site()))
+            .collect::<Vec<Ident>>();
+
+        // Diesel's conflict targets are typed, so each target is an arm of its own: any single column, or every
+        // column of a composite primary key. Columns are sorted (in declaration order) before they're matched
+        let set = quote! {
+            .do_update()
+            .set(( #( #schema::#column_names.eq(diesel::pg::upsert::excluded(#schema::#column_names)), )* ))
+            .get_result(connection)?
+        };
+        let mut targets = table_columns
+            .iter()
+            .zip(variants.iter())
+            .map(|(field, variant)| {
+                quote! {
+                    [#column_enum::#variant] => diesel::insert_into(#schema::table)
+                        .values(&row)
+                        .on_conflict(#schema::#field)
+                        #set,
+                }
+            })
+            .collect::<Vec<proc_macro2::TokenStream>>();
+        if primary_key.is_composite() {
+            let (key_fields, key_variants): (Vec<&Ident>, Vec<&Ident>) = table_columns
+                .iter()
+                .zip(variants.iter())
+                .filter(|(field, _)| primary_key.contains(field))
+                .map(|(field, variant)| (*field, variant))
+                .unzip();
+
+            targets.push(quote! {
+                [#( #column_enum::#key_variants ),*] => diesel::insert_into(#schema::table)
+                    .values(&row)
+                    .on_conflict(( #( #schema::#key_fields ),* ))
+                    #set,
+            });
+        }
+
+        quote! {
+            #[derive(juniper::GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+            #[graphql(name=#column_enum_name)]
+            pub enum #column_enum {
+                #( #variants, )*
+            }
+
+            impl #create_mutation_struct {
+                // Inserts the model, or updates the model it conflicts with on `conflict_on`
+                pub fn insert_or_update(
+                    self,
+                    conflict_on: &[#column_enum],
+                    context: &#context,
+                    connection: &<#context as BotanistContext>::Connection
+                ) -> juniper::FieldResult<#struct_name> {
+                    let mut conflict_on = conflict_on.to_vec();
+                    conflict_on.sort();
+                    conflict_on.dedup();
+
+                    if conflict_on.is_empty() {
+                        return Err(juniper::FieldError::new(
+                            "At least one column to conflict on must be given",
+                            juniper::Value::null()
+                        ));
+                    }
+
+                    let (row, children) = self.prepare(context, connection)?;
+                    let nested = vec![children];
+
+                    let create_results: Vec<#struct_name> = vec![
+                        match conflict_on.as_slice() {
+                            #( #targets )*
+                            _ => return Err(juniper::FieldError::new(
+                                "Only a single column or the whole primary key can be conflicted on",
+                                juniper::Value::null()
+                            )),
+                        }
+                    ];
+
+                    #after_insert
+
+                    Ok(create_results.into_iter().next().unwrap())
+                }
             }
 
-            impl __internal__CreateMutation<Context, #create_mutation_struct, #gql_struct_name> for #create_mutation_struct {
-                fn create(context: &#context, self_model: #create_mutation_struct) -> juniper::FieldResult<#gql_struct_name> {
+            impl __internal__Upsert<#context> for #gql_struct_name {
+                type Input = #create_mutation_struct;
+                type Column = #column_enum;
+
+                fn upsert(
+                    context: &#context,
+                    executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                    input: #create_mutation_struct,
+                    conflict_on: Vec<#column_enum>
+                ) -> juniper::FieldResult<#gql_struct_name> {
                     let connection = context.get_connection();
 
-                    diesel::insert_into(#schema::table)
-                        .values(&self_model)
-                        .get_result(&connection)
-                        .map_or_else(
-                            |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
-                            |create_result: #struct_name| Ok(#gql_struct_name::from(create_result))
-                        )
+                    context.get_loader().clear();
+
+                    let upsert_result = connection.transaction::<_, juniper::FieldError, _>(|| {
+                        input.insert_or_update(&conflict_on, context, &connection)
+                    })?;
+
+                    #gql_struct_name::preloaded(context, executor, upsert_result)
                 }
             }
-        })
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #upsert
+
+        #[derive(juniper::GraphQLInputObject)]
+        #[graphql(name=#create_mutation_struct_name)]
+        pub struct #create_mutation_struct {
+            #( #declarations, )*
+        }
+
+        #[derive(Insertable)]
+        #[table_name = #schema_str]
+        pub struct #insertable_struct {
+            #( pub #columns, )*
+        }
+
+        impl #create_mutation_struct {
+            // Runs the before hook and writes the models the model references, leaving the row to insert and the
+            // children to create once it is
+            fn prepare(
+                self,
+                context: &#context,
+                connection: &<#context as BotanistContext>::Connection
+            ) -> juniper::FieldResult<(#insertable_struct, ( #( #children_types, )* ))> {
+                let self_model = self;
+
+                #before_create
+
+                let #create_mutation_struct { #( #destructured ),* } = self_model;
+
+                // Referenced models are written first, so their keys can be
+                #( #connections )*
+
+                Ok((#insertable_struct { #( #column_names ),* }, ( #( #children_fields, )* )))
+            }
+
+            // Inserts the model, the models it references and its children, running the hooks of each. Nested
+            // writes share `connection`, and whichever transaction it's in
+            pub fn insert(
+                self,
+                context: &#context,
+                connection: &<#context as BotanistContext>::Connection
+            ) -> juniper::FieldResult<#struct_name> {
+                Ok(#create_mutation_struct::insert_all(vec![self], context, connection)?.remove(0))
+            }
+
+            // Inserts several models with a single statement, everything else is still written model by model. Models
+            // with children are inserted one at a time, RETURNING isn't promised to follow the order rows are given
+            // in and each child has to be paired with its parent
+            pub fn insert_all(
+                inputs: Vec<#create_mutation_struct>,
+                context: &#context,
+                connection: &<#context as BotanistContext>::Connection
+            ) -> juniper::FieldResult<Vec<#struct_name>> {
+                if inputs.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let mut rows = Vec::with_capacity(inputs.len());
+                let mut nested = Vec::with_capacity(inputs.len());
+
+                for input in inputs.into_iter() {
+                    let (row, children) = input.prepare(context, connection)?;
+
+                    rows.push(row);
+                    nested.push(children);
+                }
+
+                let create_results: Vec<#struct_name> = if #has_children {
+                    rows.iter()
+                        .map(|row| diesel::insert_into(#schema::table).values(row).get_result(connection))
+                        .collect::<Result<Vec<#struct_name>, diesel::result::Error>>()?
+                } else {
+                    diesel::insert_into(#schema::table)
+                        .values(&rows)
+                        .get_results(connection)?
+                };
+
+                #after_insert
+
+                Ok(create_results)
+            }
+        }
+
+        impl __internal__CreateMutation<#context, #create_mutation_struct, #gql_struct_name> for #create_mutation_struct {
+            fn create(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                self_model: #create_mutation_struct
+            ) -> juniper::FieldResult<#gql_struct_name> {
+                let connection = context.get_connection();
+
+                // Anything loaded earlier in the request may be stale after a mutation
+                context.get_loader().clear();
+
+                // Hooks and nested writes share the insert's transaction, any error rolls every one of them back
+                let create_result = connection.transaction::<_, juniper::FieldError, _>(|| {
+                    self_model.insert(context, &connection)
+                })?;
+
+                #gql_struct_name::preloaded(context, executor, create_result)
+            }
+
+            fn create_all(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                inputs: Vec<#create_mutation_struct>
+            ) -> juniper::FieldResult<Vec<#gql_struct_name>> {
+                let connection = context.get_connection();
+
+                context.get_loader().clear();
+
+                let create_results = connection.transaction::<_, juniper::FieldError, _>(|| {
+                    #create_mutation_struct::insert_all(inputs, context, &connection)
+                })?;
+
+                #gql_struct_name::preloaded_all(context, executor, create_results)
+            }
+        }
+
+        #connect_or_create
+
+        #( #create_without )*
     }
 }
 
@@ -131,6 +924,8 @@ pub fn generate_update_mutation(
     schema: &Ident,
     gql_struct_name: &Ident,
     context: &Ident,
+    primary_key: &common::PrimaryKey,
+    params: &HashMap<String, common::AttributeToken>,
 ) -> Option<proc_macro2::TokenStream> {
     let update_mutation_struct = Ident::new(
         format!("Update{}Input", struct_name).as_ref(),
@@ -140,52 +935,161 @@ pub fn generate_update_mutation(
 
     let schema_str = schema.to_string();
 
-    let tokenized_create_mutation_fields = common::tokenized_struct_fields_from_ast(
-        &ast,
-        |ident, ty| match common::type_relationship(ty) {
-            common::TypeRelationship::HasMany(_, _, _) => None,
-            common::TypeRelationship::HasOne(relationship_type, _, _) => Some(quote! {
-                pub #ident: Option<#relationship_type>
-            }),
-            common::TypeRelationship::Field => {
-                if ident == "id" {
-                    Some(quote! {
-                        pub #ident: #ty
-                    })
-                } else {
-                    Some(quote! {
-                        pub #ident: Option<#ty>
-                    })
-                }
+    let changeset_struct = Ident::new(
+        format!("{}Changeset", struct_name).as_ref(),
+        Span::call_site(),
+    );
+
+    // Nullable columns take a `Nullable` so an omitted field (left as is) can be told apart from an explicit null
+    let mut input_fields = vec![];
+    let mut changeset_fields = vec![];
+    let mut changes = vec![];
+
+    for (ident, ty, _) in common::typed_struct_fields_from_ast(ast) {
+        let (ty, nullable) = match common::type_relationship(ty) {
+            common::TypeRelationship::HasMany(_, _, _) => continue,
+            common::TypeRelationship::HasManyThrough(_, _, _, _) => continue,
+            common::TypeRelationship::HasOne(relationship_type, _, _) => {
+                let (value_ty, nullable) = common::has_one_key_type(&relationship_type);
+
+                (quote! { #value_ty }, nullable)
             }
+            common::TypeRelationship::Field => match common::option_inner_type(ty) {
+                Some(inner_ty) => (quote! { #inner_ty }, true),
+                None => (quote! { #ty }, false),
+            },
+        };
+
+        if primary_key.contains(ident) {
+            let ty = if nullable {
+                quote! { Option<#ty> }
+            } else {
+                ty
+            };
+
+            input_fields.push(quote! { pub #ident: #ty });
+            changeset_fields.push(quote! { pub #ident: #ty });
+            changes.push(quote! { #ident: input.#ident });
+        } else if nullable {
+            input_fields.push(quote! { pub #ident: juniper::Nullable<#ty> });
+            changeset_fields.push(quote! { pub #ident: Option<Option<#ty>> });
+            changes.push(quote! { #ident: input.#ident.explicit() });
+        } else {
+            input_fields.push(quote! { pub #ident: Option<#ty> });
+            changeset_fields.push(quote! { pub #ident: Option<#ty> });
+            changes.push(quote! { #ident: input.#ident });
+        }
+    }
+
+    let primary_key_columns = primary_key.columns.iter().map(|(column, _)| column);
+    let self_key = primary_key.value_of(quote! { self_model });
+    let key_matches = primary_key.matches(schema, quote! { key });
+
+    let before_update = hook(
+        params,
+        "BeforeUpdate",
+        quote! {
+            let self_model = <#struct_name as botanist::BeforeUpdate<#update_mutation_struct, #context>>::before_update(
+                self_model,
+                context,
+                connection
+            )?;
+        },
+    );
+    let after_update = hook(
+        params,
+        "AfterUpdate",
+        quote! {
+            <#struct_name as botanist::AfterUpdate<#context>>::after_update(&update_result, context, connection)?;
         },
     );
 
-    // One field just means id
-    if tokenized_create_mutation_fields.len() == 1 {
+    // Only having the primary key means there's nothing to update
+    if input_fields.len() == primary_key.columns.len() {
         None
     } else {
         Some(quote! {
-            #[derive(juniper::GraphQLInputObject, AsChangeset)]
+            #[derive(juniper::GraphQLInputObject)]
             #[graphql(name=#update_mutation_struct_name)]
-            #[table_name = #schema_str]
             pub struct #update_mutation_struct {
-                #( #tokenized_create_mutation_fields, )*
+                #( #input_fields, )*
+            }
+
+            // What's written for an update, fields that are None are left as is
+            #[derive(AsChangeset)]
+            #[table_name = #schema_str]
+            #[primary_key(#( #primary_key_columns ),*)]
+            pub struct #changeset_struct {
+                #( #changeset_fields, )*
+            }
+
+            impl From<#update_mutation_struct> for #changeset_struct {
+                fn from(input: #update_mutation_struct) -> #changeset_struct {
+                    #changeset_struct {
+                        #( #changes, )*
+                    }
+                }
+            }
+
+            impl #update_mutation_struct {
+                // Applies the changes, running the update hooks. Relationship mutations update through here too
+                pub fn apply(
+                    self,
+                    context: &#context,
+                    connection: &<#context as BotanistContext>::Connection
+                ) -> juniper::FieldResult<#struct_name> {
+                    let self_model = self;
+
+                    #before_update
+
+                    let key = #self_key;
+                    let update_result: #struct_name = diesel::update(
+                        #schema::table.filter(#key_matches)
+                    )
+                    .set(&#changeset_struct::from(self_model))
+                    .get_result(connection)?;
+
+                    #after_update
+
+                    Ok(update_result)
+                }
             }
 
             impl __internal__UpdateMutation<#context, #update_mutation_struct, #gql_struct_name> for #update_mutation_struct {
-                fn update(context: &#context, self_model: #update_mutation_struct) -> juniper::FieldResult<#gql_struct_name> {
+                fn update(
+                    context: &#context,
+                    executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                    self_model: #update_mutation_struct
+                ) -> juniper::FieldResult<#gql_struct_name> {
                     let connection = context.get_connection();
 
-                    diesel::update(
-                        #schema::table.filter(#schema::id.eq(&self_model.id))
-                    )
-                    .set(&self_model)
-                    .get_result(&connection)
-                    .map_or_else(
-                        |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
-                        |update_result: #struct_name| Ok(#gql_struct_name::from(update_result))
-                    )
+                    context.get_loader().clear();
+
+                    let update_result = connection.transaction::<_, juniper::FieldError, _>(|| {
+                        self_model.apply(context, &connection)
+                    })?;
+
+                    #gql_struct_name::preloaded(context, executor, update_result)
+                }
+
+                // Each model has changes of its own, so they're updated one by one
+                fn update_all(
+                    context: &#context,
+                    executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                    self_models: Vec<#update_mutation_struct>
+                ) -> juniper::FieldResult<Vec<#gql_struct_name>> {
+                    let connection = context.get_connection();
+
+                    context.get_loader().clear();
+
+                    let update_results = connection.transaction::<_, juniper::FieldError, _>(|| {
+                        self_models
+                            .into_iter()
+                            .map(|self_model| self_model.apply(context, &connection))
+                            .collect::<juniper::FieldResult<Vec<#struct_name>>>()
+                    })?;
+
+                    #gql_struct_name::preloaded_all(context, executor, update_results)
                 }
             }
         })
@@ -197,22 +1101,442 @@ pub fn generate_delete_mutation(
     schema: &Ident,
     gql_struct_name: &Ident,
     context: &Ident,
-    id_type: &Type,
+    primary_key: &common::PrimaryKey,
+    params: &HashMap<String, common::AttributeToken>,
 ) -> proc_macro2::TokenStream {
+    let id_type = primary_key.ty();
+    let id_matches = primary_key.matches(schema, quote! { id });
+    let ids_match = primary_key.matches_any(schema, context, quote! { ids });
+
+    let before_delete = hook(
+        params,
+        "BeforeDelete",
+        quote! {
+            <#struct_name as botanist::BeforeDelete<#id_type, #context>>::before_delete(&id, context, &connection)?;
+        },
+    );
+    let after_delete = hook(
+        params,
+        "AfterDelete",
+        quote! {
+            <#struct_name as botanist::AfterDelete<#context>>::after_delete(&delete_result, context, &connection)?;
+        },
+    );
+
     quote! {
         impl __internal__DeleteMutation<#context, #id_type, #gql_struct_name> for #gql_struct_name {
-            fn delete(context: &#context, id: #id_type) -> juniper::FieldResult<#gql_struct_name> {
+            fn delete(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                id: #id_type
+            ) -> juniper::FieldResult<#gql_struct_name> {
+                let connection = context.get_connection();
+
+                context.get_loader().clear();
+
+                let delete_result = connection.transaction::<_, juniper::FieldError, _>(|| {
+                    #before_delete
+
+                    let delete_result: #struct_name = diesel::delete(
+                        #schema::table.filter(#id_matches)
+                    )
+                    .get_result(&connection)?;
+
+                    #after_delete
+
+                    Ok(delete_result)
+                })?;
+
+                #gql_struct_name::preloaded(context, executor, delete_result)
+            }
+
+            // Keys that don't exist are skipped, only the deleted models are returned
+            fn delete_all(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                ids: Vec<#id_type>
+            ) -> juniper::FieldResult<Vec<#gql_struct_name>> {
                 let connection = context.get_connection();
 
-                diesel::delete(
-                    #schema::table.filter(#schema::id.eq(id))
+                context.get_loader().clear();
+
+                let delete_results = connection.transaction::<_, juniper::FieldError, _>(|| {
+                    for id in ids.iter() {
+                        #before_delete
+                    }
+
+                    let delete_results: Vec<#struct_name> = diesel::delete(
+                        #schema::table.filter(#ids_match)
+                    )
+                    .get_results(&connection)?;
+
+                    for delete_result in delete_results.iter() {
+                        #after_delete
+                    }
+
+                    Ok(delete_results)
+                })?;
+
+                #gql_struct_name::preloaded_all(context, executor, delete_results)
+            }
+        }
+    }
+}
+
+// Relationship mutations. Models with a forign key (a HasOne, or a column named `<name>_id`) can be connected to and
+// disconnected from the model it points at, HasMany relationships and HasOne keys named `<name>_id` get the mutations
+// managing them. None of it is offered to models with composite keys
+pub fn generate_relation_mutations(
+    ast: &DeriveInput,
+    struct_name: &Ident,
+    schema: &Ident,
+    gql_struct_name: &Ident,
+    context: &Ident,
+    primary_key: &common::PrimaryKey,
+) -> proc_macro2::TokenStream {
+    if primary_key.is_composite() {
+        return quote! {};
+    }
+
+    let update_mutation_struct = Ident::new(
+        format!("Update{}Input", struct_name).as_ref(),
+        Span::call_site(),
+    );
+    let fields = common::typed_struct_fields_from_ast(ast);
+    let id_ty = primary_key.ty();
+    let key_column = primary_key.column();
+    let key_matches = primary_key.matches(schema, quote! { key });
+    let model_key = primary_key.value_of(quote! { model });
+    let missing = format!("{} doesn't exist", struct_name);
+    let not_connected = format!("{} isn't connected", struct_name);
+
+    // Columns of the update input, everything but the key is left as is when reconnecting
+    let updatable = fields
+        .iter()
+        .filter(|(field, ty, _)| {
+            !primary_key.contains(field)
+                && matches!(
+                    common::type_relationship(ty),
+                    common::TypeRelationship::HasOne(_, _, _) | common::TypeRelationship::Field
                 )
-                .get_result(&connection)
-                .map_or_else(
-                    |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
-                    |delete_result: #struct_name| Ok(#gql_struct_name::from(delete_result))
+        })
+        .map(|(field, _, _)| *field)
+        .collect::<Vec<&Ident>>();
+
+    // Forign keys, along with the type of their (non-null) values and whether they're nullable
+    let forign_keys = fields
+        .iter()
+        .filter(|(field, _, _)| !primary_key.contains(field))
+        .filter_map(|(field, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasOne(relationship_type, _, _) => {
+                let (value_ty, nullable) = common::has_one_key_type(&relationship_type);
+
+                Some((*field, quote! { #value_ty }, nullable))
+            }
+            common::TypeRelationship::Field if field.to_string().ends_with("_id") => {
+                let value_ty = common::option_inner_type(ty).unwrap_or(ty);
+
+                Some((
+                    *field,
+                    quote! { #value_ty },
+                    common::option_inner_type(ty).is_some(),
+                ))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let reconnects = forign_keys.iter().map(|(field, value_ty, nullable)| {
+        let unchanged = updatable.iter().filter(|other| *other != field);
+        let update = |value: proc_macro2::TokenStream| {
+            let unchanged = unchanged.clone();
+
+            quote! {
+                #update_mutation_struct {
+                    #key_column: key.clone(),
+                    #field: #value,
+                    #( #unchanged: Default::default(), )*
+                }
+                .apply(context, connection)?;
+            }
+        };
+
+        let exists = quote! {
+            let query = || Ok::<_, juniper::FieldError>(#gql_struct_name::scoped_query(context)?.filter(#key_matches));
+
+            if query()?.count().get_result::<i64>(connection)? == 0 {
+                return Err(juniper::FieldError::new(#missing, juniper::Value::null()));
+            }
+        };
+
+        let (connected, disconnect) = if *nullable {
+            let update_null = update(quote! { juniper::Nullable::ExplicitNull });
+
+            (
+                update(quote! { juniper::Nullable::Some(value) }),
+                quote! {
+                    fn disconnect(
+                        key: #id_ty,
+                        value: Option<#value_ty>,
+                        context: &#context,
+                        connection: &<#context as BotanistContext>::Connection
+                    ) -> juniper::FieldResult<()> {
+                        #exists
+
+                        if let Some(value) = value {
+                            if query()?.filter(#schema::#field.eq(value)).count().get_result::<i64>(connection)? == 0 {
+                                return Err(juniper::FieldError::new(#not_connected, juniper::Value::null()));
+                            }
+                        }
+
+                        #update_null
+
+                        Ok(())
+                    }
+                },
+            )
+        } else {
+            let not_nullable = format!(
+                "{}'s {} isn't nullable, it can't be disconnected",
+                struct_name,
+                common::graphql_field_name(field)
+            );
+
+            (
+                update(quote! { Some(value) }),
+                quote! {
+                    fn disconnect(
+                        _key: #id_ty,
+                        _value: Option<#value_ty>,
+                        _context: &#context,
+                        _connection: &<#context as BotanistContext>::Connection
+                    ) -> juniper::FieldResult<()> {
+                        Err(juniper::FieldError::new(#not_nullable, juniper::Value::null()))
+                    }
+                },
+            )
+        };
+
+        quote! {
+            impl __internal__Reconnect<#schema::#field, #context> for #gql_struct_name {
+                type Key = #id_ty;
+                type Value = #value_ty;
+
+                fn connect(
+                    key: #id_ty,
+                    value: #value_ty,
+                    context: &#context,
+                    connection: &<#context as BotanistContext>::Connection
+                ) -> juniper::FieldResult<()> {
+                    #exists
+
+                    #connected
+
+                    Ok(())
+                }
+
+                #disconnect
+            }
+        }
+    });
+
+    // HasMany relationships connect (or disconnect) children by setting their forign key
+    let has_many_relations = fields
+        .iter()
+        .filter_map(|(field, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasMany(_, forign_key, model)
+                if forign_key
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string().ends_with("_id"))
+                    .unwrap_or(false) =>
+            {
+                Some((*field, forign_key, model))
+            }
+            _ => None,
+        })
+        .map(|(field, forign_key, model)| {
+            let relation_type = common::relation_type(struct_name, field);
+            let graphql_type = common::gql_struct_from_model(&model);
+            let connect = Ident::new(format!("connect_{}", field).as_ref(), Span::call_site());
+            let disconnect = Ident::new(format!("disconnect_{}", field).as_ref(), Span::call_site());
+
+            quote! {
+                // The children's keys, as taken by the mutations connecting and disconnecting them
+                pub type #relation_type = Vec<<#graphql_type as __internal__Reconnect<#forign_key, #context>>::Key>;
+
+                impl #gql_struct_name {
+                    // Children are taken from whichever model they belonged to
+                    pub fn #connect(
+                        context: &#context,
+                        executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                        key: #id_ty,
+                        ids: #relation_type
+                    ) -> juniper::FieldResult<#gql_struct_name> {
+                        #gql_struct_name::reconnect(context, executor, key, |model, connection| {
+                            for id in ids.into_iter() {
+                                <#graphql_type as __internal__Reconnect<#forign_key, #context>>::connect(
+                                    id,
+                                    #model_key,
+                                    context,
+                                    connection
+                                )?;
+                            }
+
+                            Ok(())
+                        })
+                    }
+
+                    // Only children of the model can be disconnected, and only if their forign key is nullable
+                    pub fn #disconnect(
+                        context: &#context,
+                        executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                        key: #id_ty,
+                        ids: #relation_type
+                    ) -> juniper::FieldResult<#gql_struct_name> {
+                        #gql_struct_name::reconnect(context, executor, key, |model, connection| {
+                            for id in ids.into_iter() {
+                                <#graphql_type as __internal__Reconnect<#forign_key, #context>>::disconnect(
+                                    id,
+                                    Some(#model_key),
+                                    context,
+                                    connection
+                                )?;
+                            }
+
+                            Ok(())
+                        })
+                    }
+                }
+            }
+        });
+
+    // HasOne keys named `<name>_id` are set to (existing) models, or to null if they're nullable
+    let has_one_relations = fields
+        .iter()
+        .filter(|(field, _, _)| !primary_key.contains(field))
+        .filter_map(|(field, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasOne(relationship_type, target_schema, model)
+                if field.to_string().ends_with("_id") =>
+            {
+                Some((*field, relationship_type, target_schema, model))
+            }
+            _ => None,
+        })
+        .map(|(field, relationship_type, target_schema, model)| {
+            let relation_type = common::relation_type(struct_name, field);
+            let graphql_type = common::gql_struct_from_model(&model);
+            let (_, nullable) = common::has_one_key_type(&relationship_type);
+            let target_missing = format!("{} doesn't exist", model.get_ident().unwrap());
+            let set = Ident::new(format!("set_{}", field).as_ref(), Span::call_site());
+
+            // The target is checked in the mutation's transaction, through its query modifier
+            let connect = quote! {
+                let found = #graphql_type::scoped_query(context)?
+                    .filter(#target_schema::table.primary_key().eq(value.clone()))
+                    .count()
+                    .get_result::<i64>(connection)?;
+
+                if found == 0 {
+                    return Err(juniper::FieldError::new(#target_missing, juniper::Value::null()));
+                }
+
+                <#gql_struct_name as __internal__Reconnect<#schema::#field, #context>>::connect(
+                    #model_key,
+                    value,
+                    context,
+                    connection
                 )
+            };
+            let set_value = if nullable {
+                quote! {
+                    match value {
+                        Some(value) => { #connect },
+                        None => <#gql_struct_name as __internal__Reconnect<#schema::#field, #context>>::disconnect(
+                            #model_key,
+                            None,
+                            context,
+                            connection
+                        ),
+                    }
+                }
+            } else {
+                connect
+            };
+
+            quote! {
+                // The key the relationship is set to, as taken by the mutation setting it
+                pub type #relation_type = #relationship_type;
+
+                impl #gql_struct_name {
+                    pub fn #set(
+                        context: &#context,
+                        executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                        key: #id_ty,
+                        value: #relation_type
+                    ) -> juniper::FieldResult<#gql_struct_name> {
+                        #gql_struct_name::reconnect(context, executor, key, |model, connection| {
+                            #set_value
+                        })
+                    }
+                }
+            }
+        });
+
+    quote! {
+        impl #gql_struct_name {
+            // Runs a relationship mutation against the model in a transaction, the model is reloaded afterwards as
+            // the mutation may have changed it
+            pub fn reconnect<F>(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                key: #id_ty,
+                mutation: F
+            ) -> juniper::FieldResult<#gql_struct_name>
+            where
+                F: FnOnce(&#struct_name, &<#context as BotanistContext>::Connection) -> juniper::FieldResult<()>
+            {
+                let connection = context.get_connection();
+
+                context.get_loader().clear();
+
+                let model = connection.transaction::<_, juniper::FieldError, _>(|| {
+                    let model: #struct_name = #gql_struct_name::scoped_query(context)?
+                        .filter(#key_matches)
+                        .first(&connection)
+                        .optional()?
+                        .ok_or_else(|| juniper::FieldError::new(#missing, juniper::Value::null()))?;
+
+                    mutation(&model, &connection)?;
+
+                    Ok(#schema::table.filter(#key_matches).first(&connection)?)
+                })?;
+
+                #gql_struct_name::preloaded(context, executor, model)
             }
         }
+
+        #( #reconnects )*
+
+        #( #has_many_relations )*
+
+        #( #has_one_relations )*
+    }
+}
+
+// Hooks are opt-in per model (i.e `BeforeCreate = true`), disabled hooks aren't called at all
+fn hook(
+    params: &HashMap<String, common::AttributeToken>,
+    name: &str,
+    call: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if params
+        .get(name)
+        .map(|token| token.ident == "true")
+        .unwrap_or(false)
+    {
+        call
+    } else {
+        quote! {}
     }
 }
@@ -4,7 +4,8 @@ use syn::{DeriveInput, Type};
 
 use crate::common;
 use crate::macros::mutation::{
-    generate_create_mutation, generate_delete_mutation, generate_update_mutation,
+    generate_create_mutation, generate_delete_mutation, generate_relation_mutations,
+    generate_update_mutation,
 };
 use crate::macros::query::generate_root_resolvers;
 
@@ -21,26 +22,47 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
         .ident;
     let modifies_query = params
         .get("ModifiesQuery")
-        .map(|token| token.ident.to_string() == "true")
+        .map(|token| token.ident == "true")
         .unwrap_or(false);
 
     let gql_name = struct_name.to_string();
     let gql_struct_name = Ident::new(format!("{}GQL", struct_name).as_ref(), Span::call_site());
     let query_ty = Ident::new(format!("{}Query", struct_name).as_ref(), Span::call_site());
+    let connection_struct_name = common::connection_struct(struct_name);
+    let edge_struct_name = common::edge_struct(struct_name);
+    let order_by_struct_name = common::order_by_struct(struct_name);
+    let filter_struct_name = common::filter_struct(struct_name);
+    let aggregate_struct_name = common::aggregate_struct(struct_name);
+    let aggregate_values_struct_name = Ident::new(
+        format!("{}AggregateValues", struct_name).as_ref(),
+        Span::call_site(),
+    );
 
     let struct_fields = common::typed_struct_fields_from_ast(&ast);
 
     // What kind of primary key are we using
-    let id_ty = common::typed_struct_fields_from_ast(&ast)
-        .iter()
-        .filter_map(|(ident, ty, _)| {
-            if ident.to_string() == "id" {
-                Some(ty.clone())
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<&Type>>()[0];
+    let primary_key = common::PrimaryKey::from_struct(&ast);
+    let id_ty = primary_key.ty();
+
+    // Children point back with a single forign key, which can't reference a composite key
+    if primary_key.is_composite() {
+        let children = struct_fields.iter().find(|(_, ty, _)| {
+            matches!(
+                common::type_relationship(ty),
+                common::TypeRelationship::HasMany(_, _, _)
+                    | common::TypeRelationship::HasManyThrough(_, _, _, _)
+            )
+        });
+
+        if let Some((field, _, _)) = children {
+            return syn::Error::new_spanned(
+                field,
+                "HasMany and HasManyThrough relationships need a single column primary key",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
 
     // Fields for the model and GQL structs
     let tokenized_fields =
@@ -48,6 +70,7 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
             &ast,
             |ident, ty| match common::type_relationship(ty) {
                 common::TypeRelationship::HasMany(_, _, _) => None,
+                common::TypeRelationship::HasManyThrough(_, _, _, _) => None,
                 common::TypeRelationship::HasOne(relationship_type, _, _) => Some(quote! {
                     pub #ident: #relationship_type
                 }),
@@ -63,12 +86,13 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
             .iter()
             .filter_map(|(ident, ty, _)| match common::type_relationship(ty) {
                 common::TypeRelationship::HasMany(_, _, _) => None,
+                common::TypeRelationship::HasManyThrough(_, _, _, _) => None,
                 common::TypeRelationship::HasOne(_, _, _) => None,
                 common::TypeRelationship::Field => {
                     if let Type::Path(field_type) = ty {
                         if let Some(segment) = field_type.path.segments.first() {
-                            if segment.ident.to_string() == "String" {
-                                return Some(ident.clone());
+                            if segment.ident == "String" {
+                                return Some(*ident);
                             }
                         }
                     }
@@ -77,6 +101,320 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
                 }
             });
 
+    // Columns that can be ordered by, every column of the model
+    let orderable_fields = struct_fields
+        .iter()
+        .filter_map(|(ident, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasOne(_, _, _) | common::TypeRelationship::Field => {
+                Some(*ident)
+            }
+            _ => None,
+        })
+        .collect::<Vec<&Ident>>();
+
+    let order_by_expressions = orderable_fields.iter().map(|field| {
+        quote! {
+            match &order.#field {
+                Some(botanist::SortDirection::Asc) => expressions.push(Box::new(#schema::#field.asc())),
+                Some(botanist::SortDirection::Desc) => expressions.push(Box::new(#schema::#field.desc())),
+                None => {}
+            }
+        }
+    });
+
+    // Columns that can be filtered on, along with the type of their (non-null) values and the related model's schema
+    // and GQL struct if the column is a HasOne
+    let filterable_fields = struct_fields
+        .iter()
+        .filter_map(|(ident, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasOne(relationship_type, schema, model) => {
+                let (key_ty, nullable) = common::has_one_key_type(&relationship_type);
+
+                Some((
+                    *ident,
+                    quote! { #key_ty },
+                    false,
+                    Some((schema, common::gql_struct_from_model(&model), nullable)),
+                ))
+            }
+            common::TypeRelationship::Field => {
+                let value_ty = common::option_inner_type(ty).unwrap_or(ty);
+
+                Some((
+                    *ident,
+                    quote! { #value_ty },
+                    common::is_string_type(ty),
+                    None,
+                ))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // HasMany fields can be filtered on by their children, along with the children's GQL struct and forign key
+    let filterable_relationships = struct_fields
+        .iter()
+        .filter_map(|(ident, ty, _)| match common::type_relationship(ty) {
+            common::TypeRelationship::HasMany(_, forign_key, model) => {
+                Some((*ident, common::gql_struct_from_model(&model), forign_key))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Every column gets its own filter input, typed to match the column
+    let column_filters = filterable_fields
+        .iter()
+        .map(|(field, value_ty, is_string, has_one)| {
+            let column_filter = common::column_filter_struct(struct_name, field);
+            let like = if *is_string {
+                Some(quote! { pub like: Option<String>, })
+            } else {
+                None
+            };
+
+            // HasOne columns can also be filtered by the model they point to
+            let related = has_one.as_ref().map(|(_, graphql_type, _)| {
+                quote! {
+                    pub is: Option<Box<<#graphql_type as __internal__Filterable>::Filter>>,
+                    pub is_not: Option<Box<<#graphql_type as __internal__Filterable>::Filter>>,
+                }
+            });
+
+            quote! {
+                #[derive(juniper::GraphQLInputObject, Clone, Default)]
+                pub struct #column_filter {
+                    pub eq: Option<#value_ty>,
+                    pub ne: Option<#value_ty>,
+                    pub lt: Option<#value_ty>,
+                    pub gt: Option<#value_ty>,
+                    pub r#in: Option<Vec<#value_ty>>,
+                    pub is_null: Option<bool>,
+                    #like
+                    #related
+                }
+            }
+        });
+
+    let relationship_filters = filterable_relationships
+        .iter()
+        .map(|(field, graphql_type, _)| {
+            let relationship_filter = common::column_filter_struct(struct_name, field);
+
+            quote! {
+                #[derive(juniper::GraphQLInputObject, Clone, Default)]
+                pub struct #relationship_filter {
+                    pub some: Option<Box<<#graphql_type as __internal__Filterable>::Filter>>,
+                    pub none: Option<Box<<#graphql_type as __internal__Filterable>::Filter>>,
+                    pub every: Option<Box<<#graphql_type as __internal__Filterable>::Filter>>,
+                }
+            }
+        });
+
+    let filter_fields = filterable_fields
+        .iter()
+        .map(|(field, _, _, _)| *field)
+        .chain(filterable_relationships.iter().map(|(field, _, _)| *field))
+        .map(|field| {
+            let column_filter = common::column_filter_struct(struct_name, field);
+
+            quote! {
+                pub #field: Option<#column_filter>
+            }
+        });
+
+    let filter_conditions = filterable_fields.iter().map(|(field, _, is_string, has_one)| {
+        let like = if *is_string {
+            Some(quote! {
+                if let Some(pattern) = &filter.like {
+                    conditions.push(Box::new(#schema::#field.like(pattern.clone())));
+                }
+            })
+        } else {
+            None
+        };
+
+        let related = has_one.as_ref().map(|(related_schema, graphql_type, nullable)| {
+            // Nullable keys are compared against nullable ids, and a missing model is never the one being excluded
+            if *nullable {
+                quote! {
+                    if let Some(related_filter) = &filter.is {
+                        conditions.push(Box::new(#schema::#field.eq_any(
+                            #graphql_type::filtered_query(context, related_filter)?.select(#related_schema::table.primary_key().nullable())
+                        )));
+                    }
+
+                    if let Some(related_filter) = &filter.is_not {
+                        conditions.push(Box::new(#schema::#field.ne_all(
+                            #graphql_type::filtered_query(context, related_filter)?.select(#related_schema::table.primary_key().nullable())
+                        ).or(#schema::#field.is_null())));
+                    }
+                }
+            } else {
+                quote! {
+                    if let Some(related_filter) = &filter.is {
+                        conditions.push(Box::new(#schema::#field.eq_any(
+                            #graphql_type::filtered_query(context, related_filter)?.select(#related_schema::table.primary_key())
+                        )));
+                    }
+
+                    if let Some(related_filter) = &filter.is_not {
+                        conditions.push(Box::new(#schema::#field.ne_all(
+                            #graphql_type::filtered_query(context, related_filter)?.select(#related_schema::table.primary_key())
+                        )));
+                    }
+                }
+            }
+        });
+
+        quote! {
+            if let Some(filter) = &self.#field {
+                if let Some(value) = &filter.eq {
+                    conditions.push(Box::new(#schema::#field.eq(value.clone())));
+                }
+
+                if let Some(value) = &filter.ne {
+                    conditions.push(Box::new(#schema::#field.ne(value.clone())));
+                }
+
+                if let Some(value) = &filter.lt {
+                    conditions.push(Box::new(#schema::#field.lt(value.clone())));
+                }
+
+                if let Some(value) = &filter.gt {
+                    conditions.push(Box::new(#schema::#field.gt(value.clone())));
+                }
+
+                if let Some(values) = &filter.r#in {
+                    conditions.push(Box::new(#schema::#field.eq_any(values.clone())));
+                }
+
+                match filter.is_null {
+                    Some(true) => conditions.push(Box::new(#schema::#field.is_null())),
+                    Some(false) => conditions.push(Box::new(#schema::#field.is_not_null())),
+                    None => {}
+                }
+
+                #like
+                #related
+            }
+        }
+    });
+
+    // Diesel can't correlate subqueries, so some / none / every are IN / NOT IN subqueries over the children's forign
+    // keys. Forign keys that are NULL would make every NOT IN NULL, so they're excluded
+    let relationship_conditions = filterable_relationships.iter().map(|(field, graphql_type, forign_key)| {
+        let key = primary_key.column();
+
+        quote! {
+            if let Some(filter) = &self.#field {
+                if let Some(child_filter) = &filter.some {
+                    conditions.push(Box::new(#schema::#key.eq_any(
+                        #graphql_type::filtered_query(context, child_filter)?.select(#forign_key)
+                    )));
+                }
+
+                if let Some(child_filter) = &filter.none {
+                    conditions.push(Box::new(#schema::#key.ne_all(
+                        #graphql_type::filtered_query(context, child_filter)?
+                            .filter(#forign_key.is_not_null())
+                            .select(#forign_key)
+                    )));
+                }
+
+                // Every child matches if no child fails to match
+                if let Some(child_filter) = &filter.every {
+                    conditions.push(Box::new(#schema::#key.ne_all(
+                        #graphql_type::excluded_query(context, child_filter)?
+                            .filter(#forign_key.is_not_null())
+                            .select(#forign_key)
+                    )));
+                }
+            }
+        }
+    });
+
+    // Numeric columns can be aggregated, primary keys and forign keys aside
+    let aggregate_resolvers = struct_fields
+        .iter()
+        .filter(|(ident, ty, _)| {
+            matches!(
+                common::type_relationship(ty),
+                common::TypeRelationship::Field
+            ) && !primary_key.contains(ident)
+                && common::is_numeric_type(ty)
+        })
+        .map(|(field, _, _)| {
+            common::blocking_resolver(quote! {
+                pub fn #field(&self, context: &#context_ty) -> juniper::FieldResult<Option<f64>> {
+                    let query = #gql_struct_name::filtered_query(context, &self.filter)?;
+                    let connection = context.get_connection();
+
+                    Ok(match self.function {
+                        aggregate::AggregateFunction::Avg => query
+                            .select(aggregate::as_double(diesel::dsl::avg(#schema::#field)))
+                            .get_result::<Option<f64>>(&connection)?,
+                        aggregate::AggregateFunction::Sum => query
+                            .select(aggregate::as_double(diesel::dsl::sum(#schema::#field)))
+                            .get_result::<Option<f64>>(&connection)?,
+                        aggregate::AggregateFunction::Min => query
+                            .select(aggregate::as_double(diesel::dsl::min(#schema::#field)))
+                            .get_result::<Option<f64>>(&connection)?,
+                        aggregate::AggregateFunction::Max => query
+                            .select(aggregate::as_double(diesel::dsl::max(#schema::#field)))
+                            .get_result::<Option<f64>>(&connection)?,
+                    })
+                }
+            })
+        })
+        .collect::<Vec<proc_macro2::TokenStream>>();
+
+    // Without any numeric columns, there's nothing but a count to aggregate
+    let (aggregate_functions, aggregate_values) = if aggregate_resolvers.is_empty() {
+        (None, None)
+    } else {
+        (
+            Some(quote! {
+                pub fn avg(&self) -> #aggregate_values_struct_name {
+                    self.values(aggregate::AggregateFunction::Avg)
+                }
+
+                pub fn sum(&self) -> #aggregate_values_struct_name {
+                    self.values(aggregate::AggregateFunction::Sum)
+                }
+
+                pub fn min(&self) -> #aggregate_values_struct_name {
+                    self.values(aggregate::AggregateFunction::Min)
+                }
+
+                pub fn max(&self) -> #aggregate_values_struct_name {
+                    self.values(aggregate::AggregateFunction::Max)
+                }
+            }),
+            Some(quote! {
+                #[derive(Clone)]
+                pub struct #aggregate_values_struct_name {
+                    function: aggregate::AggregateFunction,
+                    filter: #filter_struct_name,
+                }
+
+                #[juniper::graphql_object(Context = Context, scalar = juniper::DefaultScalarValue)]
+                impl #aggregate_values_struct_name {
+                    #( #aggregate_resolvers )*
+                }
+
+                impl #aggregate_struct_name {
+                    fn values(&self, function: aggregate::AggregateFunction) -> #aggregate_values_struct_name {
+                        #aggregate_values_struct_name {
+                            function,
+                            filter: self.filter.clone(),
+                        }
+                    }
+                }
+            }),
+        )
+    };
+
     // Fields to implement std::From on the GQL struct for the model
     let tokenized_from_fields =
         common::tokenized_struct_fields_from_ast(
@@ -84,9 +422,18 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
             |ident, ty| match common::type_relationship(ty) {
                 common::TypeRelationship::HasMany(_, _, _) => {
                     let preload_field = common::preload_field(ident);
+                    let count_preload_field = common::preload_field(&common::count_field(ident));
+
+                    Some(quote! {
+                        #preload_field: Arc::new(Mutex::new(std::collections::HashMap::new())),
+                        #count_preload_field: Arc::new(Mutex::new(std::collections::HashMap::new()))
+                    })
+                }
+                common::TypeRelationship::HasManyThrough(_, _, _, _) => {
+                    let preload_field = common::preload_field(ident);
 
                     Some(quote! {
-                        #preload_field: Arc::new(Mutex::new(RefCell::new(None)))
+                        #preload_field: Arc::new(Mutex::new(std::collections::HashMap::new()))
                     })
                 }
                 common::TypeRelationship::HasOne(_, _, _) => {
@@ -94,7 +441,7 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
 
                     Some(quote! {
                         #ident: model.#ident,
-                        #preload_field: Arc::new(Mutex::new(RefCell::new(None)))
+                        #preload_field: Arc::new(Mutex::new(std::collections::HashMap::new()))
                     })
                 }
                 common::TypeRelationship::Field => Some(quote! {
@@ -104,12 +451,130 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
         );
 
     // Juniper resolver functions
-    let resolvers = struct_fields.iter().map(|(field, ty, _)| {
+    let resolvers = struct_fields.iter().map(|(field, ty, attrs)| {
         match common::type_relationship(ty) {
-            common::TypeRelationship::HasMany(schema, forign_key, model) => {
+            common::TypeRelationship::HasMany(_, _, model) => {
                 let (preload_field, graphql_type) = common::get_type_info(field, &model);
+                let key = primary_key.column();
+
+                // Counting children doesn't require loading them
+                let count_field = common::count_field(field);
+                let count_preload_field = common::preload_field(&count_field);
+
+                // Children are loaded for every parent handed out alongside this one
+                let relation = common::relation(&gql_struct_name, field);
+                let count_relation = common::relation(&gql_struct_name, &count_field);
+                let load_field = common::load_field(field);
+                let load_count_field = common::load_field(&count_field);
+
+                let count_resolver = common::blocking_resolver(quote! {
+                    pub fn #count_field(
+                        &self,
+                        context: &#context_ty,
+                        executor: &Executor<#context_ty, juniper::DefaultScalarValue>
+                    ) -> juniper::FieldResult<i32> {
+                        let look_ahead = executor.look_ahead();
+
+                        if let Ok(preload) = self.#count_preload_field.lock() {
+                            if let Some(preloaded) = preload.get(look_ahead.field_name()) {
+                                Ok(*preloaded)
+                            } else {
+                                Ok(context
+                                    .get_loader()
+                                    .load(#relation, #count_relation, &self.#key, |ids| #gql_struct_name::#load_count_field(context, ids))?
+                                    .unwrap_or(0))
+                            }
+                        } else {
+                            Err(juniper::FieldError::new("Failed to acquire lock on preload field!", juniper::Value::null()))
+                        }
+                    }
+                });
+
+                // Opt-in Relay style connection, resolved alongside the plain list. A page of every deferred parent's
+                // children is loaded at once, like the list
+                let connection_resolver = if common::field_options(attrs)
+                    .get("connection")
+                    .map(|token| token.ident == "true")
+                    .unwrap_or(false)
+                {
+                    let connection_type = common::connection_struct(model.get_ident().unwrap());
+                    let connection_field = common::connection_field(field);
+                    let connection_relation = common::relation(&gql_struct_name, &connection_field);
+                    let load_connection_field = common::load_field(&connection_field);
+
+                    Some(common::blocking_resolver(quote! {
+                        pub fn #connection_field(
+                            &self,
+                            context: &#context_ty,
+                            executor: &Executor<#context_ty, juniper::DefaultScalarValue>,
+                            first: Option<i32>,
+                            after: Option<String>,
+                            last: Option<i32>,
+                            before: Option<String>
+                        ) -> juniper::FieldResult<#connection_type> {
+                            let look_ahead = executor.look_ahead();
+                            // Pages are preloaded for the selection they're loaded for
+                            let variant = format!("{}{}", #connection_relation, macro_helpers::selection_key(&look_ahead));
+
+                            context
+                                .get_loader()
+                                .load(#relation, &variant, &self.#key, |ids| {
+                                    #gql_struct_name::#load_connection_field(context, ids, first, after, last, before, &look_ahead)
+                                })?
+                                .ok_or_else(|| juniper::FieldError::new("Failed to load connection!", juniper::Value::null()))
+                        }
+                    }))
+                } else {
+                    None
+                };
+
+                let list_resolver = common::blocking_resolver(quote! {
+                    pub fn #field(
+                        &self,
+                        context: &#context_ty,
+                        executor: &Executor<#context_ty, juniper::DefaultScalarValue>,
+                        limit: Option<i32>,
+                        offset: Option<i32>,
+                        order_by: Option<Vec<<#graphql_type as __internal__Orderable>::OrderBy>>
+                    ) -> juniper::FieldResult<Vec<#graphql_type>> {
+                        let look_ahead = executor.look_ahead();
+
+                        if let Ok(preload) = self.#preload_field.lock() {
+                            if let Some(preloaded) = preload.get(look_ahead.field_name()) {
+                                Ok(preloaded.clone())
+                            } else {
+                                let limit = limit.unwrap_or(10);
+                                let offset = offset.unwrap_or(0);
+                                let order_by = order_by.unwrap_or_default();
+                                // Children are preloaded for the selection they're loaded for
+                                let variant = format!("{}{}", #relation, macro_helpers::selection_key(&look_ahead));
+
+                                Ok(context
+                                    .get_loader()
+                                    .load(#relation, &variant, &self.#key, |ids| {
+                                        #gql_struct_name::#load_field(context, ids, limit, offset, &order_by, &look_ahead)
+                                    })?
+                                    .unwrap_or_default())
+                            }
+                        } else {
+                            Err(juniper::FieldError::new("Failed to acquire lock on preload field!", juniper::Value::null()))
+                        }
+                    }
+                });
 
                 quote! {
+                    #connection_resolver
+                    #count_resolver
+                    #list_resolver
+                }
+            },
+            common::TypeRelationship::HasManyThrough(_, _, _, model) => {
+                let (preload_field, graphql_type) = common::get_type_info(field, &model);
+                let key = primary_key.column();
+                let relation = common::relation(&gql_struct_name, field);
+                let load_field = common::load_field(field);
+
+                common::blocking_resolver(quote! {
                     pub fn #field(
                         &self,
                         context: &#context_ty,
@@ -117,68 +582,101 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
                         limit: Option<i32>,
                         offset: Option<i32>
                     ) -> juniper::FieldResult<Vec<#graphql_type>> {
-                        if let Ok(preload) = self.#preload_field.clone().lock() {
-                            if preload.borrow().is_some() {
-                                Ok(preload.replace_with(|_| None).unwrap())
+                        let look_ahead = executor.look_ahead();
+
+                        if let Ok(preload) = self.#preload_field.lock() {
+                            if let Some(preloaded) = preload.get(look_ahead.field_name()) {
+                                Ok(preloaded.clone())
                             } else {
-                                #schema::table
-                                    .filter(#forign_key.eq(&self.id))
-                                    .limit(limit.unwrap_or(10) as i64)
-                                    .offset(offset.unwrap_or(0) as i64)
-                                    .load::<#model>(&context.get_connection())
-                                    .map_or_else(
-                                        |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
-                                        |models| {
-                                            let gql_models = models.iter().map(
-                                                |model| #graphql_type::from(model.to_owned())
-                                            ).collect::<Vec<#graphql_type>>();
-
-                                            let preload_result = #graphql_type::preload_children(&
-                                                gql_models,
-                                                &context,
-                                                &executor.look_ahead()
-                                            );
-
-                                            if let Err(preload_err) = preload_result {
-                                                Err(juniper::FieldError::new(preload_err.to_string(), juniper::Value::null()))
-                                            } else {
-                                                Ok(gql_models)
-                                            }
-                                        }
-                                    )
+                                let limit = limit.unwrap_or(10);
+                                let offset = offset.unwrap_or(0);
+                                let variant = format!("{}{}", #relation, macro_helpers::selection_key(&look_ahead));
+
+                                Ok(context
+                                    .get_loader()
+                                    .load(#relation, &variant, &self.#key, |ids| {
+                                        #gql_struct_name::#load_field(context, ids, limit, offset, &look_ahead)
+                                    })?
+                                    .unwrap_or_default())
                             }
                         } else {
                             Err(juniper::FieldError::new("Failed to acquire lock on preload field!", juniper::Value::null()))
                         }
                     }
-                }
+                })
             },
-            common::TypeRelationship::HasOne(_, schema, model) => {
+            common::TypeRelationship::HasOne(relationship_type, schema, model) => {
                 let (preload_field, graphql_type) = common::get_type_info(field, &model);
 
-                quote! {
+                // Children are shared by every model referencing them, so they're loaded by the child's type
+                let relation = graphql_type.to_string();
+                let load_child = |id: proc_macro2::TokenStream| quote! {
+                    context
+                        .get_loader()
+                        .load(#relation, &format!("{}{}", #relation, macro_helpers::selection_key(&look_ahead)), #id, |ids| {
+                            let gql_models = #graphql_type::scoped_query(context)?
+                                .filter(#schema::table.primary_key().eq_any(ids))
+                                .load::<#model>(&context.get_connection())?
+                                .into_iter()
+                                .map(#graphql_type::from)
+                                .collect::<Vec<#graphql_type>>();
+
+                            #graphql_type::preload_children(&gql_models, context, &look_ahead)?;
+
+                            Ok::<_, juniper::FieldError>(gql_models.into_iter().map(|model| (model.primary_key(), model)).collect())
+                        })?
+                };
+                let load_nullable_child = load_child(quote! { id });
+                let load_child = load_child(quote! { &self.#field });
+
+                // Nullable keys resolve to a nullable field, without a query when there's nothing to load
+                if common::has_one_key_type(&relationship_type).1 {
+                    return common::blocking_resolver(quote! {
+                        pub fn #field(
+                            &self,
+                            context: &#context_ty,
+                            executor: &Executor<#context_ty, juniper::DefaultScalarValue>
+                        ) -> juniper::FieldResult<Option<#graphql_type>> {
+                            let look_ahead = executor.look_ahead();
+
+                            if let Ok(preload) = self.#preload_field.lock() {
+                                if let Some(preloaded) = preload.get(look_ahead.field_name()) {
+                                    Ok(Some(preloaded.clone()))
+                                } else if let Some(id) = &self.#field {
+                                    // Missing rows are as good as a null key
+                                    Ok(#load_nullable_child)
+                                } else {
+                                    Ok(None)
+                                }
+                            } else {
+                                Err(juniper::FieldError::new("Failed to acquire lock on preload field!", juniper::Value::null()))
+                            }
+                        }
+                    });
+                }
+
+                common::blocking_resolver(quote! {
                     pub fn #field(
                         &self,
                         context: &#context_ty,
                         executor: &Executor<#context_ty, juniper::DefaultScalarValue>
                     ) -> juniper::FieldResult<#graphql_type> {
-                        if let Ok(preload) = self.#preload_field.clone().lock() {
-                            if preload.borrow().is_some() {
-                                Ok(preload.replace_with(|_| None).unwrap())
+                        let look_ahead = executor.look_ahead();
+
+                        if let Ok(preload) = self.#preload_field.lock() {
+                            if let Some(preloaded) = preload.get(look_ahead.field_name()) {
+                                Ok(preloaded.clone())
                             } else {
-                                #schema::table
-                                    .filter(#schema::id.eq(&self.#field))
-                                    .get_result::<#model>(&context.get_connection())
-                                    .map_or_else(
-                                        |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
-                                        |model| Ok(#graphql_type::from(model.to_owned()))
-                                    )
+                                #load_child.ok_or_else(|| juniper::FieldError::new(
+                                    diesel::result::Error::NotFound.to_string(),
+                                    juniper::Value::null()
+                                ))
                             }
                         } else {
                             Err(juniper::FieldError::new("Failed to acquire lock on preload field!", juniper::Value::null()))
                         }
                     }
-                }
+                })
             },
             common::TypeRelationship::Field => quote! {
                 pub fn #field(&self, context: &#context_ty) -> &#ty {
@@ -196,82 +694,88 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
                 let (preload_field, graphql_type) = common::get_type_info(field, &model);
 
                 Some(quote! {
-                    #preload_field: Arc<Mutex<RefCell<Option<#graphql_type>>>>
+                    #preload_field: Arc<Mutex<std::collections::HashMap<String, #graphql_type>>>
                 })
             }
             common::TypeRelationship::HasMany(_, _, model) => {
                 let (preload_field, graphql_type) = common::get_type_info(field, &model);
+                let count_preload_field = common::preload_field(&common::count_field(field));
 
                 Some(quote! {
-                    #preload_field: Arc<Mutex<RefCell<Option<Vec<#graphql_type>>>>>
-                })
-            }
-            _ => None,
-        })
-        .filter(|field| field.is_some());
-
-    // Model id extractors; extract ids from models for preloading
-    let id_extractors = struct_fields
-        .iter()
-        .map(|(field, ty, _)| match common::type_relationship(ty) {
-            common::TypeRelationship::HasOne(_, _, model) => {
-                let str_model = model.get_ident().unwrap().to_string();
-
-                Some(quote! {
-                    type_to_ids
-                        .entry(#str_model)
-                        .or_insert(Vec::new())
-                        .push(self_model.#field);
+                    #preload_field: Arc<Mutex<std::collections::HashMap<String, Vec<#graphql_type>>>>,
+                    #count_preload_field: Arc<Mutex<std::collections::HashMap<String, i32>>>
                 })
             }
-            common::TypeRelationship::HasMany(_, _, model) => {
-                let str_model = model.get_ident().unwrap().to_string();
+            common::TypeRelationship::HasManyThrough(_, _, _, model) => {
+                let (preload_field, graphql_type) = common::get_type_info(field, &model);
 
                 Some(quote! {
-                    type_to_ids
-                        .entry(#str_model)
-                        .or_insert(Vec::new())
-                        .push(self_model.id);
+                    #preload_field: Arc<Mutex<std::collections::HashMap<String, Vec<#graphql_type>>>>
                 })
             }
             _ => None,
         })
-        .filter(|extractor| extractor.is_some());
+        .filter(|field| field.is_some());
 
     // Logic blocks for relations before their resolvers are explicity invoked
     let preloaders = struct_fields.iter().map(|(field, ty, _)| {
         match common::type_relationship(ty) {
-            common::TypeRelationship::HasOne(_, schema, model) => {
-                let str_model = model.get_ident().unwrap().to_string();
-                let str_field = field.to_string();
+            common::TypeRelationship::HasOne(relationship_type, schema, model) => {
+                let graphql_field = common::graphql_field_name(field);
 
                 let (preload_field, graphql_type) = common::get_type_info(field, &model);
+                let relation = graphql_type.to_string();
+                let (key_ty, nullable) = common::has_one_key_type(&relationship_type);
+
+                // Null keys have nothing to preload
+                let (distinct_ids, child_model) = if nullable {
+                    (
+                        quote! { self_models.iter().filter_map(|self_model| self_model.#field.clone()) },
+                        quote! { self_model.#field.as_ref().and_then(|id| distinct_id_to_gql_model.get(id)) },
+                    )
+                } else {
+                    (
+                        quote! { self_models.iter().map(|self_model| self_model.#field.clone()) },
+                        quote! { distinct_id_to_gql_model.get(&self_model.#field) },
+                    )
+                };
 
                 Some(quote! {
                     {
-                        if let Some(look_ahead_selection) = look_ahead.select_child(#str_field) {
-                            if let Some(mut distinct_ids) = type_to_ids.get_mut(#str_model) {
-                                distinct_ids.sort();
-                                distinct_ids.dedup();
+                        let mut distinct_ids = #distinct_ids.collect::<Vec<#key_ty>>();
 
-                                let models = #schema::table
-                                    .filter(#schema::id.eq_any(&*distinct_ids))
-                                    .load::<#model>(&context.get_connection())?;
+                        if !distinct_ids.is_empty() {
+                            distinct_ids.sort();
+                            distinct_ids.dedup();
 
-                                let gql_models = models.into_iter().map(
-                                    |model| #graphql_type::from(model)
-                                ).collect::<Vec<#graphql_type>>();
+                            // Unaliased selections of the field are preloaded, keyed by their response name
+                            for look_ahead_selection in macro_helpers::select_children(look_ahead, #graphql_field) {
+                                // Children already loaded for the same selection come from the loader
+                                let distinct_id_to_gql_model = context.get_loader().load_many(
+                                    &format!("{}{}", #relation, macro_helpers::selection_key(look_ahead_selection)),
+                                    &distinct_ids,
+                                    |ids| {
+                                        let gql_models = #graphql_type::scoped_query(context)?
+                                            .filter(#schema::table.primary_key().eq_any(ids))
+                                            .load::<#model>(&context.get_connection())?
+                                            .into_iter()
+                                            .map(#graphql_type::from)
+                                            .collect::<Vec<#graphql_type>>();
 
-                                #graphql_type::preload_children(&gql_models, &context, &look_ahead_selection)?;
+                                        #graphql_type::preload_children(&gql_models, &context, &look_ahead_selection)?;
 
-                                let distinct_id_to_gql_model: HashMap<&#id_ty, #graphql_type> = HashMap::from_iter(
-                                    distinct_ids.iter().zip(gql_models.into_iter())
-                                );
+                                        // Rows come back in no particular order and may be missing, so they're matched by key
+                                        Ok::<HashMap<#key_ty, #graphql_type>, juniper::FieldError>(gql_models
+                                            .into_iter()
+                                            .map(|model| (model.primary_key(), model))
+                                            .collect())
+                                    }
+                                )?;
 
                                 for self_model in self_models.iter() {
-                                    if let Some(child_model) = distinct_id_to_gql_model.get(&self_model.#field) {
-                                        if let Ok(preload) = self_model.#preload_field.clone().lock() {
-                                            preload.replace_with(|_| Some(child_model.clone()));
+                                    if let Some(child_model) = #child_model {
+                                        if let Ok(mut preload) = self_model.#preload_field.lock() {
+                                            preload.insert(look_ahead_selection.field_name().to_string(), child_model.clone());
                                         }
                                     }
                                 }
@@ -280,51 +784,110 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
                     }
                 })
             },
-            common::TypeRelationship::HasMany(schema, forign_key_path, model) => {
-                let str_model = model.get_ident().unwrap().to_string();
-                let str_field = field.to_string();
+            common::TypeRelationship::HasMany(_, _, model) => {
+                let graphql_field = common::graphql_field_name(field);
 
                 let (preload_field, graphql_type) = common::get_type_info(field, &model);
-                let forign_key = forign_key_path.segments.last().unwrap();
+                let load_field = common::load_field(field);
+
+                let count_field = common::count_field(field);
+                let graphql_count_field = common::graphql_field_name(&count_field);
+                let count_preload_field = common::preload_field(&count_field);
+                let load_count_field = common::load_field(&count_field);
+                let count_relation = common::relation(&gql_struct_name, &count_field);
+                let key = primary_key.column();
 
                 Some(quote! {
                     {
-                        if let Some(look_ahead_selection) = look_ahead.select_child(#str_field) {
-                            if let Some(mut forign_key_ids) = type_to_ids.get_mut(#str_model) {
-                                forign_key_ids.sort();
-                                forign_key_ids.dedup();
+                        let mut forign_key_ids = self_models.iter().map(|self_model| self_model.#key.clone()).collect::<Vec<#id_ty>>();
 
-                                let limit = macro_helpers::int_argument_from_look_ahead(look_ahead, "limit", 10);
-                                let offset = macro_helpers::int_argument_from_look_ahead(look_ahead, "offset", 0);
+                        if !forign_key_ids.is_empty() {
+                            forign_key_ids.sort();
+                            forign_key_ids.dedup();
 
-                                let models = #schema::table
-                                    .filter(#schema::#forign_key.eq_any(&*forign_key_ids))
-                                    .limit(limit as i64)
-                                    .offset(offset as i64)
-                                    .load::<#model>(&context.get_connection())?;
+                            // Counts take no arguments, so every selection (aliased ones included, through the
+                            // loader) shares a single load
+                            let count_selections = macro_helpers::select_children(look_ahead, #graphql_count_field);
+                            let forign_key_to_count = if count_selections.is_empty() {
+                                std::collections::HashMap::new()
+                            } else {
+                                context.get_loader().load_many(#count_relation, &forign_key_ids, |ids| {
+                                    #gql_struct_name::#load_count_field(context, ids)
+                                })?
+                            };
 
-                                let gql_models = models.into_iter().map(
-                                    |model| #graphql_type::from(model)
-                                ).collect::<Vec<#graphql_type>>();
+                            for look_ahead_selection in count_selections {
+                                for self_model in self_models.iter() {
+                                    let count = forign_key_to_count.get(&self_model.#key).cloned().unwrap_or(0);
 
-                                #graphql_type::preload_children(&gql_models, &context, &look_ahead_selection)?;
+                                    if let Ok(mut preload) = self_model.#count_preload_field.lock() {
+                                        preload.insert(look_ahead_selection.field_name().to_string(), count);
+                                    }
+                                }
+                            }
 
-                                let mut forign_key_to_models: HashMap<#id_ty, Vec<#graphql_type>> = HashMap::new();
+                            // Unaliased selections of the field are preloaded, keyed by their response name
+                            for look_ahead_selection in macro_helpers::select_children(look_ahead, #graphql_field) {
+                                let limit = macro_helpers::int_argument_from_look_ahead(look_ahead_selection, "limit", 10);
+                                let offset = macro_helpers::int_argument_from_look_ahead(look_ahead_selection, "offset", 0);
+                                let order_by = macro_helpers::input_argument_from_look_ahead::<
+                                    Vec<<#graphql_type as __internal__Orderable>::OrderBy>
+                                >(look_ahead_selection, "orderBy").unwrap_or_default();
 
-                                for model in gql_models.into_iter() {
-                                    forign_key_to_models
-                                        .entry(model.#forign_key.clone())
-                                        .or_insert(Vec::new())
-                                        .push(model);
+                                let forign_key_to_models = #gql_struct_name::#load_field(
+                                    context,
+                                    &forign_key_ids,
+                                    limit,
+                                    offset,
+                                    &order_by,
+                                    look_ahead_selection
+                                )?;
+
+                                for self_model in self_models.iter() {
+                                    let child_models = forign_key_to_models.get(&self_model.#key).cloned().unwrap_or_default();
+
+                                    if let Ok(mut preload) = self_model.#preload_field.lock() {
+                                        preload.insert(look_ahead_selection.field_name().to_string(), child_models);
+                                    }
                                 }
+                            }
+                        }
+                    }
+                })
+            },
+            common::TypeRelationship::HasManyThrough(_, _, _, model) => {
+                let graphql_field = common::graphql_field_name(field);
+
+                let (preload_field, _) = common::get_type_info(field, &model);
+                let load_field = common::load_field(field);
+                let key = primary_key.column();
+
+                Some(quote! {
+                    {
+                        let mut local_ids = self_models.iter().map(|self_model| self_model.#key.clone()).collect::<Vec<#id_ty>>();
+
+                        if !local_ids.is_empty() {
+                            local_ids.sort();
+                            local_ids.dedup();
+
+                            // Unaliased selections of the field are preloaded, keyed by their response name
+                            for look_ahead_selection in macro_helpers::select_children(look_ahead, #graphql_field) {
+                                let limit = macro_helpers::int_argument_from_look_ahead(look_ahead_selection, "limit", 10);
+                                let offset = macro_helpers::int_argument_from_look_ahead(look_ahead_selection, "offset", 0);
+
+                                let local_id_to_models = #gql_struct_name::#load_field(
+                                    context,
+                                    &local_ids,
+                                    limit,
+                                    offset,
+                                    look_ahead_selection
+                                )?;
 
                                 for self_model in self_models.iter() {
-                                    if let Some(child_models) = forign_key_to_models.get(&self_model.id) {
-                                        if let Ok(preload) = self_model.#preload_field.clone().lock() {
-                                            preload.replace_with(
-                                                |_| Some(child_models.clone())
-                                            );
-                                        }
+                                    let child_models = local_id_to_models.get(&self_model.#key).cloned().unwrap_or_default();
+
+                                    if let Ok(mut preload) = self_model.#preload_field.lock() {
+                                        preload.insert(look_ahead_selection.field_name().to_string(), child_models);
                                     }
                                 }
                             }
@@ -336,53 +899,460 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
         }
     });
 
-    // Top level query modifier stub, if ModifiesQuery isn't set, provide a default implementation
-    let query_modifier = if modifies_query {
-        quote! {}
-    } else {
+    // Batched loads of each relationship, shared by the preloaders and the loader
+    let relation_loaders = struct_fields.iter().map(|(field, ty, attrs)| {
+        match common::type_relationship(ty) {
+            common::TypeRelationship::HasMany(schema, forign_key_path, model) => {
+                let graphql_type = common::gql_struct_from_model(&model);
+                let forign_key = forign_key_path.segments.last().unwrap();
+                let load_field = common::load_field(field);
+                let count_field = common::count_field(field);
+                let count_relation = common::relation(&gql_struct_name, &count_field);
+                let load_count_field = common::load_field(&count_field);
+
+                let load_connection = if common::field_options(attrs)
+                    .get("connection")
+                    .map(|token| token.ident == "true")
+                    .unwrap_or(false)
+                {
+                    let connection_type = common::connection_struct(model.get_ident().unwrap());
+                    let load_connection_field = common::load_field(&common::connection_field(field));
+
+                    Some(quote! {
+                        // Pages apply to each parent, not to every child we're loading
+                        pub fn #load_connection_field(
+                            context: &#context_ty,
+                            ids: &[#id_ty],
+                            first: Option<i32>,
+                            after: Option<String>,
+                            last: Option<i32>,
+                            before: Option<String>,
+                            look_ahead: &juniper::LookAheadSelection<juniper::DefaultScalarValue>
+                        ) -> juniper::FieldResult<std::collections::HashMap<#id_ty, #connection_type>> {
+                            let (backwards, page_size, after, before) = #connection_type::page_arguments(first, after, last, before)?;
+
+                            let query = #connection_type::page_query(
+                                #graphql_type::scoped_query(context)?.filter(#schema::#forign_key.eq_any(ids)),
+                                &after,
+                                &before
+                            );
+
+                            // Loading one extra row of each parent's children tells us if there's another page
+                            let models = if <#context_ty as BotanistContext>::SUPPORTS_WINDOW_FUNCTIONS {
+                                partition::partitioned_limit(
+                                    query,
+                                    #schema::table,
+                                    #schema::all_columns,
+                                    #schema::#forign_key,
+                                    #connection_type::key_order(backwards),
+                                    page_size as i64 + 1,
+                                    0
+                                )
+                                .load::<#model>(&context.get_connection())?
+                            } else {
+                                let mut forign_key_to_row: std::collections::HashMap<#id_ty, i32> = std::collections::HashMap::new();
+                                let mut query = query.order(#schema::#forign_key);
+
+                                for expression in #connection_type::key_order(backwards) {
+                                    query = query.then_order_by(expression);
+                                }
+
+                                query
+                                    .load::<#model>(&context.get_connection())?
+                                    .into_iter()
+                                    .filter(|model| {
+                                        let row = forign_key_to_row.entry(model.#forign_key.clone()).or_insert(0);
+                                        *row += 1;
+
+                                        *row <= page_size + 1
+                                    })
+                                    .collect::<Vec<#model>>()
+                            };
+
+                            // Every parent's children are counted at once, shared with the count resolver
+                            let forign_key_to_count = if macro_helpers::selects_total_count(look_ahead) {
+                                context.get_loader().load_many(#count_relation, ids, |ids| {
+                                    #gql_struct_name::#load_count_field(context, ids)
+                                })?
+                            } else {
+                                std::collections::HashMap::new()
+                            };
+
+                            let mut forign_key_to_models: std::collections::HashMap<#id_ty, Vec<#model>> = ids
+                                .iter()
+                                .map(|id| (id.clone(), Vec::new()))
+                                .collect();
+
+                            for model in models.into_iter() {
+                                forign_key_to_models
+                                    .entry(model.#forign_key.clone())
+                                    .or_insert(Vec::new())
+                                    .push(model);
+                            }
+
+                            let forign_key_to_connection = forign_key_to_models
+                                .into_iter()
+                                .map(|(forign_key, models)| {
+                                    let total_count = forign_key_to_count.get(&forign_key).cloned().unwrap_or(0);
+                                    let connection = #connection_type::from_page(
+                                        models,
+                                        page_size,
+                                        backwards,
+                                        after.is_some(),
+                                        before.is_some(),
+                                        total_count
+                                    );
+
+                                    (forign_key, connection)
+                                })
+                                .collect::<std::collections::HashMap<#id_ty, #connection_type>>();
+
+                            #connection_type::preload_nodes(forign_key_to_connection.values(), context, look_ahead)?;
+
+                            Ok(forign_key_to_connection)
+                        }
+                    })
+                } else {
+                    None
+                };
+
+                Some(quote! {
+                    #load_connection
+
+                    // Parents without any children still get an entry
+                    pub fn #load_count_field(
+                        context: &#context_ty,
+                        ids: &[#id_ty]
+                    ) -> juniper::FieldResult<std::collections::HashMap<#id_ty, i32>> {
+                        let mut forign_key_to_count: std::collections::HashMap<#id_ty, i32> = ids
+                            .iter()
+                            .map(|id| (id.clone(), 0))
+                            .collect();
+
+                        let counts = #graphql_type::scoped_query(context)?
+                            .filter(#schema::#forign_key.eq_any(ids))
+                            .group_by(#schema::#forign_key)
+                            // Diesel 1.4 won't select an aggregate alongside a column, but it will a literal
+                            .select((#schema::#forign_key, diesel::dsl::sql::<diesel::sql_types::BigInt>("COUNT(*)")))
+                            .load::<(#id_ty, i64)>(&context.get_connection())?;
+
+                        for (forign_key, count) in counts.into_iter() {
+                            forign_key_to_count.insert(forign_key, count as i32);
+                        }
+
+                        Ok(forign_key_to_count)
+                    }
+
+                    pub fn #load_field(
+                        context: &#context_ty,
+                        ids: &[#id_ty],
+                        limit: i32,
+                        offset: i32,
+                        order_by: &[<#graphql_type as __internal__Orderable>::OrderBy],
+                        look_ahead: &juniper::LookAheadSelection<juniper::DefaultScalarValue>
+                    ) -> juniper::FieldResult<std::collections::HashMap<#id_ty, Vec<#graphql_type>>> {
+                        // Children are ordered as requested, ties are broken by their primary key
+                        let mut order_by_expressions = #graphql_type::order_by_expressions(order_by);
+                        order_by_expressions.push(Box::new(#schema::table.primary_key().asc()));
+
+                        let query = #graphql_type::scoped_query(context)?.filter(#schema::#forign_key.eq_any(ids));
+
+                        // Limits and offsets apply to each parent, not to every child we're loading
+                        let models = if <#context_ty as BotanistContext>::SUPPORTS_WINDOW_FUNCTIONS {
+                            partition::partitioned_limit(
+                                query,
+                                #schema::table,
+                                #schema::all_columns,
+                                #schema::#forign_key,
+                                order_by_expressions,
+                                limit as i64,
+                                offset as i64
+                            )
+                            .load::<#model>(&context.get_connection())?
+                        } else {
+                            let mut forign_key_to_row: std::collections::HashMap<#id_ty, i32> = std::collections::HashMap::new();
+                            let mut query = query.order(#schema::#forign_key);
+
+                            for expression in order_by_expressions {
+                                query = query.then_order_by(expression);
+                            }
+
+                            query
+                                .load::<#model>(&context.get_connection())?
+                                .into_iter()
+                                .filter(|model| {
+                                    let row = forign_key_to_row.entry(model.#forign_key.clone()).or_insert(0);
+                                    *row += 1;
+
+                                    *row > offset && *row <= offset + limit
+                                })
+                                .collect::<Vec<#model>>()
+                        };
+
+                        let gql_models = models.into_iter().map(
+                            |model| #graphql_type::from(model)
+                        ).collect::<Vec<#graphql_type>>();
+
+                        #graphql_type::preload_children(&gql_models, &context, look_ahead)?;
+
+                        let mut forign_key_to_models: std::collections::HashMap<#id_ty, Vec<#graphql_type>> = ids
+                            .iter()
+                            .map(|id| (id.clone(), Vec::new()))
+                            .collect();
+
+                        for model in gql_models.into_iter() {
+                            forign_key_to_models
+                                .entry(model.#forign_key.clone())
+                                .or_insert(Vec::new())
+                                .push(model);
+                        }
+
+                        Ok(forign_key_to_models)
+                    }
+                })
+            },
+            common::TypeRelationship::HasManyThrough(join_schema, local_key, remote_key, model) => {
+                let graphql_type = common::gql_struct_from_model(&model);
+                let load_field = common::load_field(field);
+
+                Some(quote! {
+                    pub fn #load_field(
+                        context: &#context_ty,
+                        ids: &[#id_ty],
+                        limit: i32,
+                        offset: i32,
+                        look_ahead: &juniper::LookAheadSelection<juniper::DefaultScalarValue>
+                    ) -> juniper::FieldResult<std::collections::HashMap<#id_ty, Vec<#graphql_type>>> {
+                        // Children are joined through the join table in a single query, the child's query modifier
+                        // applies as a subquery since its (boxed) query can't be joined
+                        let remote = <#graphql_type as __internal__Table>::table();
+                        let query = remote
+                            .inner_join(#join_schema::table.on(#remote_key.eq(remote.primary_key())))
+                            .filter(#local_key.eq_any(ids))
+                            .filter(remote.primary_key().eq_any(#graphql_type::scoped_query(context)?.select(remote.primary_key())));
+                        let columns = <<#graphql_type as __internal__Table>::Table as diesel::Table>::all_columns();
+
+                        // Limits and offsets apply to each parent, after the query modifier has hidden what it will
+                        let rows = if <#context_ty as BotanistContext>::SUPPORTS_WINDOW_FUNCTIONS {
+                            partition::numbered_limit(
+                                query.select((partition::row_number(#local_key, remote.primary_key()), #local_key, columns)),
+                                limit as i64,
+                                offset as i64
+                            )
+                            .load::<(i64, #id_ty, #model)>(&context.get_connection())?
+                            .into_iter()
+                            .map(|(_, local_id, model)| (local_id, model))
+                            .collect::<Vec<(#id_ty, #model)>>()
+                        } else {
+                            let mut local_id_to_row: std::collections::HashMap<#id_ty, i32> = std::collections::HashMap::new();
+
+                            query
+                                .order((#local_key, remote.primary_key()))
+                                .select((#local_key, columns))
+                                .load::<(#id_ty, #model)>(&context.get_connection())?
+                                .into_iter()
+                                .filter(|(local_id, _)| {
+                                    let row = local_id_to_row.entry(local_id.clone()).or_insert(0);
+                                    *row += 1;
+
+                                    *row > offset && *row <= offset + limit
+                                })
+                                .collect::<Vec<(#id_ty, #model)>>()
+                        };
+                        let rows = rows
+                            .into_iter()
+                            .map(|(local_id, model)| (local_id, #graphql_type::from(model)))
+                            .collect::<Vec<(#id_ty, #graphql_type)>>();
+
+                        let gql_models = rows.iter().map(|(_, model)| model.clone()).collect::<Vec<#graphql_type>>();
+
+                        #graphql_type::preload_children(&gql_models, &context, look_ahead)?;
+
+                        let mut local_id_to_models: std::collections::HashMap<#id_ty, Vec<#graphql_type>> = ids
+                            .iter()
+                            .map(|id| (id.clone(), Vec::new()))
+                            .collect();
+
+                        for (local_id, model) in rows.into_iter() {
+                            local_id_to_models
+                                .entry(local_id)
+                                .or_insert(Vec::new())
+                                .push(model);
+                        }
+
+                        Ok(local_id_to_models)
+                    }
+                })
+            },
+            _ => None
+        }
+    });
+
+    // Keys each relationship will be resolved by, deferred to the loader whenever models are handed out
+    let deferrals = struct_fields.iter().map(|(field, ty, _)| {
+        match common::type_relationship(ty) {
+            common::TypeRelationship::HasOne(relationship_type, _, model) => {
+                let relation = common::gql_struct_from_model(&model).to_string();
+
+                let keys = if common::has_one_key_type(&relationship_type).1 {
+                    quote! { self_models.iter().filter_map(|self_model| self_model.#field.clone()) }
+                } else {
+                    quote! { self_models.iter().map(|self_model| self_model.#field.clone()) }
+                };
+
+                Some(quote! {
+                    context.get_loader().defer(#relation, #keys);
+                })
+            },
+            common::TypeRelationship::HasMany(_, _, _) | common::TypeRelationship::HasManyThrough(_, _, _, _) => {
+                let relation = common::relation(&gql_struct_name, field);
+                let key = primary_key.column();
+
+                Some(quote! {
+                    context.get_loader().defer(#relation, self_models.iter().map(|self_model| self_model.#key.clone()));
+                })
+            },
+            _ => None
+        }
+    });
+
+    // Every query loading this model goes through the internal modifier, which defers to the user's QueryModifier
+    // when ModifiesQuery is set and leaves the query alone otherwise
+    let modify_query = if modifies_query {
         quote! {
-           impl<'a> __internal__DefaultQueryModifier<#query_ty<'a>, #context_ty> for #struct_name {
-                fn modify_query(query: #query_ty<'a>, context: &#context_ty) -> Result<#query_ty<'a>, juniper::FieldError> {
-                       Ok(query)
-                }
-           }
+            <#struct_name as botanist::QueryModifier<#query_ty<'a>, #context_ty>>::modify_query(query, context)
+        }
+    } else {
+        quote! { Ok(query) }
+    };
+    let query_modifier = quote! {
+        impl<'a> __internal__DefaultQueryModifier<#query_ty<'a>, #context_ty> for #struct_name {
+            fn modify_query(query: #query_ty<'a>, context: &#context_ty) -> Result<#query_ty<'a>, juniper::FieldError> {
+                #modify_query
+            }
         }
     };
 
     // Mutations
-    let create_mutation =
-        generate_create_mutation(&ast, &struct_name, &schema, &gql_struct_name, &context_ty);
-    let update_mutation =
-        generate_update_mutation(&ast, &struct_name, &schema, &gql_struct_name, &context_ty);
-    let delete_mutation =
-        generate_delete_mutation(&struct_name, &schema, &gql_struct_name, &context_ty, &id_ty);
+    let create_mutation = generate_create_mutation(
+        &ast,
+        struct_name,
+        &schema,
+        &gql_struct_name,
+        context_ty,
+        &primary_key,
+        &params,
+    );
+    let update_mutation = generate_update_mutation(
+        &ast,
+        struct_name,
+        &schema,
+        &gql_struct_name,
+        context_ty,
+        &primary_key,
+        &params,
+    );
+    let relation_mutations = generate_relation_mutations(
+        &ast,
+        struct_name,
+        &schema,
+        &gql_struct_name,
+        context_ty,
+        &primary_key,
+    );
+    let delete_mutation = generate_delete_mutation(
+        struct_name,
+        &schema,
+        &gql_struct_name,
+        context_ty,
+        &primary_key,
+        &params,
+    );
+
+    // Keys are loaded and exposed the same way whether they're a single column or several
+    let ids_match = primary_key.matches_any(&schema, context_ty, quote! { ids });
+    let self_key = primary_key.value_of(quote! { self });
+
+    // Composite keys are passed to the singular and delete resolvers as an input object
+    let (key_struct, key_ty) = if primary_key.is_composite() {
+        let key_struct_name = common::key_struct(struct_name);
+        let key_fields = primary_key
+            .columns
+            .iter()
+            .map(|(column, ty)| quote! { pub #column: #ty });
+        let key_values = primary_key.value_of(quote! { key });
+
+        (
+            Some(quote! {
+                #[derive(juniper::GraphQLInputObject, Clone)]
+                pub struct #key_struct_name {
+                    #( #key_fields, )*
+                }
+
+                impl From<#key_struct_name> for #id_ty {
+                    fn from(key: #key_struct_name) -> #id_ty {
+                        #key_values
+                    }
+                }
+            }),
+            quote! { #key_struct_name },
+        )
+    } else {
+        (None, id_ty.clone())
+    };
+
+    // Connections are paginated by keyset on the primary key
+    let decode_after = primary_key.decode_cursor(quote! { cursor });
+    let decode_before = decode_after.clone();
+    let follows_after = primary_key.follows(&schema, quote! { after }, false);
+    let precedes_before = primary_key.follows(&schema, quote! { before }, true);
+    let key_ascending = primary_key.orders(&schema, false);
+    let key_descending = primary_key.orders(&schema, true);
+    let encode_cursor = primary_key.encode_cursor(quote! { node });
 
     // Query Root Resolvers
     let root_resolvers = generate_root_resolvers(
-        &struct_name,
+        struct_name,
         &schema,
         &gql_struct_name,
-        &context_ty,
-        &id_ty,
+        context_ty,
+        &primary_key,
         searchable_fields,
     );
 
+    let aggregate_count = common::blocking_resolver(quote! {
+        pub fn count(&self, context: &#context_ty) -> juniper::FieldResult<i32> {
+            self.count_matching(context)
+        }
+    });
+
     let attrs = &ast.attrs;
     let gen = quote! {
         use diesel::prelude::*;
 
         use botanist::internal::{
+            __internal__ConnectOrCreate,
             __internal__CreateMutation,
+            __internal__CreateWithout,
             __internal__UpdateMutation,
             __internal__DeleteMutation,
+            __internal__Filterable,
+            __internal__Keyable,
+            __internal__Loadable,
+            __internal__Orderable,
             __internal__Preloadable,
+            __internal__Reconnect,
+            __internal__Upsert,
             __internal__RootResolver,
             __internal__DefaultQueryModifier,
+            __internal__Table,
         };
         use botanist::macro_helpers;
+        use botanist::diesel_extensions::aggregate;
+        use botanist::diesel_extensions::filter as filter_extensions;
+        use botanist::diesel_extensions::partition;
         use botanist::diesel_extensions::prefix_search;
         use botanist::Context as BotanistContext;
-        use std::cell::RefCell;
         use std::sync::Mutex;
         use std::sync::Arc;
 
@@ -397,7 +1367,7 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
             #( #tokenized_fields, )*
         }
 
-        // Useful query type alias and default modifier (if the user isn't specifying one)
+        // Useful query type alias and the modifier every query of the model goes through
         type #query_ty<'a> = #schema::BoxedQuery<'a, <#context_ty as BotanistContext>::DB>;
         #query_modifier
 
@@ -423,20 +1393,408 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
 
         impl __internal__Preloadable<Context, #gql_struct_name> for #gql_struct_name {
             fn preload_children(
-                self_models: &Vec<#gql_struct_name>,
+                self_models: &[#gql_struct_name],
                 context: &#context_ty,
                 look_ahead: &juniper::LookAheadSelection<juniper::DefaultScalarValue>
-            ) -> Result<(), diesel::result::Error> {
+            ) -> juniper::FieldResult<()> {
                 use std::collections::HashMap;
-                use std::iter::FromIterator;
 
-                let mut type_to_ids: HashMap<&str, Vec<#id_ty>> = HashMap::new();
+                #gql_struct_name::defer_children(self_models, context);
+
+                #( #preloaders )*
+
+                Ok(())
+            }
+        }
+
+        impl __internal__Loadable<Context, #id_ty, #gql_struct_name> for #gql_struct_name {
+            fn load_by_ids(
+                context: &#context_ty,
+                ids: &[#id_ty]
+            ) -> juniper::FieldResult<Vec<#gql_struct_name>> {
+                Ok(#gql_struct_name::scoped_query(context)?
+                    .filter(#ids_match)
+                    .load::<#struct_name>(&context.get_connection())?
+                    .into_iter()
+                    .map(#gql_struct_name::from)
+                    .collect())
+            }
+
+            fn primary_key(&self) -> #id_ty {
+                #self_key
+            }
+        }
+
+        #key_struct
+
+        impl __internal__Keyable for #gql_struct_name {
+            type Key = #key_ty;
+        }
+
+        impl __internal__Table for #gql_struct_name {
+            type Table = #schema::table;
+
+            fn table() -> #schema::table {
+                #schema::table
+            }
+        }
+
+        impl #gql_struct_name {
+            // Single models (i.e from a singular resolver or a mutation) are preloaded like any other
+            pub fn preloaded(
+                context: &#context_ty,
+                executor: &juniper::Executor<#context_ty, juniper::DefaultScalarValue>,
+                model: #struct_name
+            ) -> juniper::FieldResult<#gql_struct_name> {
+                let gql_model = #gql_struct_name::from(model);
+
+                #gql_struct_name::preload_children(std::slice::from_ref(&gql_model), context, &executor.look_ahead())?;
 
-                for self_model in self_models.iter() {
-                    #( #id_extractors )*
+                Ok(gql_model)
+            }
+
+            pub fn preloaded_all(
+                context: &#context_ty,
+                executor: &juniper::Executor<#context_ty, juniper::DefaultScalarValue>,
+                models: Vec<#struct_name>
+            ) -> juniper::FieldResult<Vec<#gql_struct_name>> {
+                let gql_models = models.into_iter().map(#gql_struct_name::from).collect::<Vec<#gql_struct_name>>();
+
+                #gql_struct_name::preload_children(&gql_models, context, &executor.look_ahead())?;
+
+                Ok(gql_models)
+            }
+
+            // Lets relationships of models handed out by hand written resolvers be loaded together
+            pub fn defer_children(self_models: &[#gql_struct_name], context: &#context_ty) {
+                #( #deferrals )*
+            }
+
+            #( #relation_loaders )*
+        }
+
+        // Ordering input, accepted by plural resolvers as a list of columns to order by
+        #[derive(juniper::GraphQLInputObject, Clone, Debug)]
+        pub struct #order_by_struct_name {
+            #( pub #orderable_fields: Option<botanist::SortDirection>, )*
+        }
+
+        impl __internal__Orderable for #gql_struct_name {
+            type OrderBy = #order_by_struct_name;
+        }
+
+        impl #gql_struct_name {
+            pub fn order_by_expressions(
+                order_by: &[#order_by_struct_name]
+            ) -> Vec<Box<dyn diesel::expression::BoxableExpression<#schema::table, <#context_ty as BotanistContext>::DB, SqlType = ()>>> {
+                let mut expressions: Vec<Box<dyn diesel::expression::BoxableExpression<#schema::table, <#context_ty as BotanistContext>::DB, SqlType = ()>>> = Vec::new();
+
+                for order in order_by.iter() {
+                    #( #order_by_expressions )*
                 }
 
-                #( #preloaders )*
+                expressions
+            }
+        }
+
+        // Filtering input, accepted by plural resolvers as `where`
+        #( #column_filters )*
+        #( #relationship_filters )*
+
+        #[derive(juniper::GraphQLInputObject, Clone, Default)]
+        pub struct #filter_struct_name {
+            #( #filter_fields, )*
+            #[graphql(name = "_or")]
+            pub _or: Option<Vec<#filter_struct_name>>,
+        }
+
+        impl __internal__Filterable for #gql_struct_name {
+            type Filter = #filter_struct_name;
+        }
+
+        impl #filter_struct_name {
+            // Every condition of a filter must match, None if there aren't any
+            pub fn to_expression(
+                &self,
+                context: &#context_ty
+            ) -> juniper::FieldResult<Option<Box<dyn diesel::expression::BoxableExpression<#schema::table, <#context_ty as BotanistContext>::DB, SqlType = diesel::sql_types::Bool>>>> {
+                let mut conditions: Vec<Box<dyn diesel::expression::BoxableExpression<#schema::table, <#context_ty as BotanistContext>::DB, SqlType = diesel::sql_types::Bool>>> = Vec::new();
+
+                #( #filter_conditions )*
+                #( #relationship_conditions )*
+
+                // An empty branch matches everything, as does the _or as a whole
+                if let Some(branches) = &self._or {
+                    let branches = branches
+                        .iter()
+                        .map(|branch| branch.to_expression(context))
+                        .collect::<juniper::FieldResult<Option<Vec<_>>>>()?;
+
+                    if let Some(any_branch) = branches.and_then(|branches| {
+                        branches.into_iter().reduce(|left, right| Box::new(left.or(right)))
+                    }) {
+                        conditions.push(any_branch);
+                    }
+                }
+
+                Ok(conditions.into_iter().reduce(|left, right| Box::new(left.and(right))))
+            }
+        }
+
+        impl #gql_struct_name {
+            // Every model the query modifier lets through, relationships load their models from this so they're
+            // scoped the same way root resolvers are
+            pub fn scoped_query<'a>(context: &#context_ty) -> juniper::FieldResult<#query_ty<'a>> {
+                <#struct_name as __internal__DefaultQueryModifier<_, #context_ty>>::modify_query(#schema::table.into_boxed(), context)
+            }
+
+            // Models matching a filter, as seen through the query modifier. Other models filter across their
+            // relationships with this
+            pub fn filtered_query(
+                context: &#context_ty,
+                filter: &#filter_struct_name
+            ) -> juniper::FieldResult<#query_ty<'static>> {
+                let mut query = #schema::table.into_boxed();
+
+                if let Some(expression) = filter.to_expression(context)? {
+                    query = query.filter(expression);
+                }
+
+                <#struct_name as __internal__DefaultQueryModifier<_, #context_ty>>::modify_query(query, context)
+            }
+
+            // Models not matching a filter, including those the filter evaluates to NULL for. An empty filter
+            // matches every model, so none are excluded
+            pub fn excluded_query(
+                context: &#context_ty,
+                filter: &#filter_struct_name
+            ) -> juniper::FieldResult<#query_ty<'static>> {
+                let query = match filter.to_expression(context)? {
+                    Some(expression) => #schema::table.into_boxed().filter(filter_extensions::is_not_true(expression)),
+                    None => #schema::table.into_boxed().filter(false.into_sql::<diesel::sql_types::Bool>()),
+                };
+
+                <#struct_name as __internal__DefaultQueryModifier<_, #context_ty>>::modify_query(query, context)
+            }
+        }
+
+        // Aggregates over every model matching a filter, each aggregate is its own query
+        #[derive(Clone)]
+        pub struct #aggregate_struct_name {
+            filter: #filter_struct_name,
+        }
+
+        impl #aggregate_struct_name {
+            pub fn new(filter: #filter_struct_name) -> #aggregate_struct_name {
+                #aggregate_struct_name { filter }
+            }
+
+            pub fn count_matching(&self, context: &#context_ty) -> juniper::FieldResult<i32> {
+                Ok(#gql_struct_name::filtered_query(context, &self.filter)?
+                    .count()
+                    .get_result::<i64>(&context.get_connection())? as i32)
+            }
+        }
+
+        #[juniper::graphql_object(Context = Context, scalar = juniper::DefaultScalarValue)]
+        impl #aggregate_struct_name {
+            #aggregate_count
+
+            #aggregate_functions
+        }
+
+        #aggregate_values
+
+        // Relay connection types, only exposed if a resolver opts in to returning them
+        #[derive(Clone)]
+        pub struct #edge_struct_name {
+            cursor: String,
+            node: #gql_struct_name,
+        }
+
+        #[juniper::graphql_object(Context = Context, scalar = juniper::DefaultScalarValue)]
+        impl #edge_struct_name {
+            pub fn cursor(&self) -> &String {
+                &self.cursor
+            }
+
+            pub fn node(&self) -> &#gql_struct_name {
+                &self.node
+            }
+        }
+
+        #[derive(Clone)]
+        pub struct #connection_struct_name {
+            edges: Vec<#edge_struct_name>,
+            page_info: botanist::connection::PageInfo,
+            total_count: i32,
+        }
+
+        #[juniper::graphql_object(Context = Context, scalar = juniper::DefaultScalarValue)]
+        impl #connection_struct_name {
+            pub fn edges(&self) -> &Vec<#edge_struct_name> {
+                &self.edges
+            }
+
+            pub fn page_info(&self) -> &botanist::connection::PageInfo {
+                &self.page_info
+            }
+
+            pub fn total_count(&self) -> i32 {
+                self.total_count
+            }
+        }
+
+        impl #connection_struct_name {
+            // Loads a page of the query, paginated by keyset on the primary key
+            pub fn load<'a>(
+                context: &#context_ty,
+                look_ahead: &juniper::LookAheadSelection<juniper::DefaultScalarValue>,
+                query: impl Fn() -> juniper::FieldResult<#query_ty<'a>>,
+                first: Option<i32>,
+                after: Option<String>,
+                last: Option<i32>,
+                before: Option<String>
+            ) -> juniper::FieldResult<#connection_struct_name> {
+                let (backwards, page_size, after, before) = #connection_struct_name::page_arguments(first, after, last, before)?;
+
+                let mut page_query = #connection_struct_name::page_query(query()?, &after, &before);
+
+                for expression in #connection_struct_name::key_order(backwards) {
+                    page_query = page_query.then_order_by(expression);
+                }
+
+                let connection = context.get_connection();
+
+                // Loading one extra row tells us if there's another page
+                let models = page_query
+                    .limit(page_size as i64 + 1)
+                    .load::<#struct_name>(&connection)?;
+
+                // Counting is a second query, only run it if it's been asked for
+                let total_count = if macro_helpers::selects_total_count(look_ahead) {
+                    query()?.count().get_result::<i64>(&connection)? as i32
+                } else {
+                    0
+                };
+
+                let page = #connection_struct_name::from_page(models, page_size, backwards, after.is_some(), before.is_some(), total_count);
+
+                #connection_struct_name::preload_nodes(std::iter::once(&page), context, look_ahead)?;
+
+                Ok(page)
+            }
+
+            // The direction, size and cursors of the requested page. Parents loading a page of every parent's
+            // children at once share them with `load`
+            pub fn page_arguments(
+                first: Option<i32>,
+                after: Option<String>,
+                last: Option<i32>,
+                before: Option<String>
+            ) -> juniper::FieldResult<(bool, i32, Option<#id_ty>, Option<#id_ty>)> {
+                if first.is_some() && last.is_some() {
+                    return Err(juniper::FieldError::new("first and last can't be combined", juniper::Value::null()));
+                }
+
+                // Paginating with last takes the rows just before the cursor, rather than just after it
+                let backwards = last.is_some();
+                let page_size = last.or(first).unwrap_or(10);
+
+                if page_size < 0 {
+                    return Err(juniper::FieldError::new("first and last can't be negative", juniper::Value::null()));
+                }
+
+                let after = after.map(|cursor| #decode_after).transpose()?;
+                let before = before.map(|cursor| #decode_before).transpose()?;
+
+                Ok((backwards, page_size, after, before))
+            }
+
+            // Narrows the query to the rows between the cursors
+            pub fn page_query<'a>(
+                mut query: #query_ty<'a>,
+                after: &Option<#id_ty>,
+                before: &Option<#id_ty>
+            ) -> #query_ty<'a> {
+                if let Some(after) = after {
+                    query = query.filter(#follows_after);
+                }
+
+                if let Some(before) = before {
+                    query = query.filter(#precedes_before);
+                }
+
+                query
+            }
+
+            // Pages are ordered by the primary key, backwards when paginating with last
+            pub fn key_order(
+                backwards: bool
+            ) -> Vec<Box<dyn diesel::expression::BoxableExpression<#schema::table, <#context_ty as BotanistContext>::DB, SqlType = ()>>> {
+                let mut expressions: Vec<Box<dyn diesel::expression::BoxableExpression<#schema::table, <#context_ty as BotanistContext>::DB, SqlType = ()>>> = Vec::new();
+
+                if backwards {
+                    #( expressions.push(Box::new(#key_descending)); )*
+                } else {
+                    #( expressions.push(Box::new(#key_ascending)); )*
+                }
+
+                expressions
+            }
+
+            // Builds the connection from the rows of a page, which may include one extra row telling us if there's
+            // another page
+            pub fn from_page(
+                mut models: Vec<#struct_name>,
+                page_size: i32,
+                backwards: bool,
+                after: bool,
+                before: bool,
+                total_count: i32
+            ) -> #connection_struct_name {
+                let has_more = models.len() > page_size as usize;
+                models.truncate(page_size as usize);
+
+                if backwards {
+                    models.reverse();
+                }
+
+                let edges = models
+                    .into_iter()
+                    .map(#gql_struct_name::from)
+                    .map(|node| #edge_struct_name {
+                        cursor: #encode_cursor,
+                        node,
+                    })
+                    .collect::<Vec<#edge_struct_name>>();
+
+                #connection_struct_name {
+                    page_info: botanist::connection::PageInfo {
+                        has_next_page: if backwards { before } else { has_more },
+                        has_previous_page: if backwards { has_more } else { after },
+                        start_cursor: edges.first().map(|edge| edge.cursor.clone()),
+                        end_cursor: edges.last().map(|edge| edge.cursor.clone()),
+                    },
+                    edges,
+                    total_count,
+                }
+            }
+
+            // Preloads the nodes of every connection at once
+            pub fn preload_nodes<'c>(
+                connections: impl IntoIterator<Item = &'c #connection_struct_name>,
+                context: &#context_ty,
+                look_ahead: &juniper::LookAheadSelection<juniper::DefaultScalarValue>
+            ) -> juniper::FieldResult<()> {
+                let nodes = connections
+                    .into_iter()
+                    .flat_map(|connection| connection.edges.iter().map(|edge| edge.node.clone()))
+                    .collect::<Vec<#gql_struct_name>>();
+
+                for node in macro_helpers::select_connection_nodes(look_ahead) {
+                    #gql_struct_name::preload_children(&nodes, context, node)?;
+                }
 
                 Ok(())
             }
@@ -445,6 +1803,7 @@ pub fn botanist_object(attrs: TokenStream, input: TokenStream) -> TokenStream {
         #create_mutation
         #update_mutation
         #delete_mutation
+        #relation_mutations
 
         #root_resolvers
     };
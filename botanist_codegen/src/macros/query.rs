@@ -14,14 +14,10 @@ pub fn botanist_query(attrs: TokenStream, input: TokenStream) -> TokenStream {
             .get("Context")
             .expect("a context must be specified")
             .ident;
-        let primary_key_ty = &params
-            .get("PrimaryKey")
-            .expect("a primary key type must be specified")
-            .ident;
 
         let (root_resolvers, query_types): (Vec<_>, Vec<_>)  = query_models.map(|rich_model| {
             let model = &rich_model.ident;
-            let graphql_type = common::gql_struct(&model);
+            let graphql_type = common::gql_struct(model);
             let model_name = model.to_string();
 
             let singular = Ident::new(common::lower_first(&model_name).as_ref(), Span::call_site());
@@ -51,6 +47,14 @@ pub fn botanist_query(attrs: TokenStream, input: TokenStream) -> TokenStream {
                 Span::call_site()
             );
 
+            let has_connection = rich_model.arguments.get("connection").map(
+                |token| token.ident == "true"
+            ).unwrap_or(false);
+
+            let has_aggregates = rich_model.arguments.get("aggregates").map(
+                |token| token.ident == "true"
+            ).unwrap_or(false);
+
             let searchable_fields = rich_model.arguments.get("searchable").map(|token| &token.arguments);
 
             // Generate the searchable fields of the query type
@@ -80,7 +84,7 @@ pub fn botanist_query(attrs: TokenStream, input: TokenStream) -> TokenStream {
                     let query_field_inserters = searchable_fields.map(
                         |args| args.keys().map(
                             |key| {
-                                let ki = Ident::new(&key, Span::call_site());
+                                let ki = Ident::new(key, Span::call_site());
         
                                 quote! {
                                     if let Some(value) = &self.#ki {
@@ -135,44 +139,133 @@ pub fn botanist_query(attrs: TokenStream, input: TokenStream) -> TokenStream {
 
             // Types must be marked as 'all' (all their records can be fetched) in order to enable field searching
             let plural_resolver = if can_fetch_all {
-                quote! {
+                let plural_query_argument = query_argument.iter();
+                let search_query = query_getter.clone().unwrap_or(quote! { None });
+
+                common::blocking_resolver(quote! {
                     fn #plural(
                         context: &#context_ty,
                         executor: &Executor,
-                        ids: Option<Vec<#primary_key_ty>>,
+                        ids: Option<Vec<<#graphql_type as __internal__Keyable>::Key>>,
                         limit: Option<i32>,
                         offset: Option<i32>,
-                        #query_argument,
+                        order_by: Option<Vec<<#graphql_type as __internal__Orderable>::OrderBy>>,
+                        r#where: Option<<#graphql_type as __internal__Filterable>::Filter>,
+                        #( #plural_query_argument, )*
                     ) -> juniper::FieldResult<Vec<#graphql_type>> {
-                        #model::resolve_multiple(context, executor, ids, limit, offset, #query_getter)
+                        let ids = ids.map(|ids| ids.into_iter().map(Into::into).collect());
+
+                        #model::resolve_multiple(context, executor, ids, limit, offset, order_by, r#where, #search_query)
                     }
-                }
+                })
             } else {
-                quote! {
+                common::blocking_resolver(quote! {
                     fn #plural(
                         context: &#context_ty,
                         executor: &Executor,
-                        ids: Vec<#primary_key_ty>,
+                        ids: Vec<<#graphql_type as __internal__Keyable>::Key>,
                         limit: Option<i32>,
-                        offset: Option<i32>
+                        offset: Option<i32>,
+                        order_by: Option<Vec<<#graphql_type as __internal__Orderable>::OrderBy>>,
+                        r#where: Option<<#graphql_type as __internal__Filterable>::Filter>
                     ) -> juniper::FieldResult<Vec<#graphql_type>> {
-                        #model::resolve_multiple(context, executor, Some(ids), limit, offset, None)
+                        let ids = ids.into_iter().map(Into::into).collect();
+
+                        #model::resolve_multiple(context, executor, Some(ids), limit, offset, order_by, r#where, None)
                     }
-                }
+                })
             };
 
-            (quote! {
-                fn #singular(context: &#context_ty, id: #primary_key_ty) -> juniper::FieldResult<#graphql_type> {
-                    #model::resolve_single(context, id)
+            // Relay style connection, paginated with cursors rather than limits and offsets
+            let connection_resolver = if has_connection {
+                let connection_type = common::connection_struct(model);
+                let connection_field = Ident::new(format!("{}_connection", plural).as_ref(), Span::call_site());
+
+                let (ids_argument, ids_getter) = if can_fetch_all {
+                    (
+                        quote! { ids: Option<Vec<<#graphql_type as __internal__Keyable>::Key>> },
+                        quote! { ids.map(|ids| ids.into_iter().map(Into::into).collect()) },
+                    )
+                } else {
+                    (
+                        quote! { ids: Vec<<#graphql_type as __internal__Keyable>::Key> },
+                        quote! { Some(ids.into_iter().map(Into::into).collect()) },
+                    )
+                };
+
+                let (connection_query_argument, connection_query_getter) = match (&query_argument, &query_getter) {
+                    (Some(argument), Some(getter)) if can_fetch_all => (Some(quote! { , #argument }), quote! { #getter }),
+                    _ => (None, quote! { None }),
+                };
+
+                Some(common::blocking_resolver(quote! {
+                    fn #connection_field(
+                        context: &#context_ty,
+                        executor: &Executor,
+                        #ids_argument,
+                        r#where: Option<<#graphql_type as __internal__Filterable>::Filter>,
+                        first: Option<i32>,
+                        after: Option<String>,
+                        last: Option<i32>,
+                        before: Option<String>
+                        #connection_query_argument
+                    ) -> juniper::FieldResult<#connection_type> {
+                        #model::resolve_connection(context, executor, #ids_getter, r#where, first, after, last, before, #connection_query_getter)
+                    }
+                }))
+            } else {
+                None
+            };
+
+            // Counts and aggregates over every model matching a filter
+            let aggregate_resolvers = if has_aggregates {
+                let aggregate_type = common::aggregate_struct(model);
+                let count_field = Ident::new(format!("{}_count", singular).as_ref(), Span::call_site());
+                let aggregate_field = Ident::new(format!("{}_aggregate", singular).as_ref(), Span::call_site());
+
+                let count_resolver = common::blocking_resolver(quote! {
+                    fn #count_field(
+                        context: &#context_ty,
+                        r#where: Option<<#graphql_type as __internal__Filterable>::Filter>
+                    ) -> juniper::FieldResult<i32> {
+                        #aggregate_type::new(r#where.unwrap_or_default()).count_matching(context)
+                    }
+                });
+
+                Some(quote! {
+                    #count_resolver
+
+                    fn #aggregate_field(
+                        r#where: Option<<#graphql_type as __internal__Filterable>::Filter>
+                    ) -> #aggregate_type {
+                        #aggregate_type::new(r#where.unwrap_or_default())
+                    }
+                })
+            } else {
+                None
+            };
+
+            let singular_resolver = common::blocking_resolver(quote! {
+                fn #singular(
+                    context: &#context_ty,
+                    executor: &Executor,
+                    id: <#graphql_type as __internal__Keyable>::Key
+                ) -> juniper::FieldResult<#graphql_type> {
+                    #model::resolve_single(context, executor, id.into())
                 }
+            });
 
+            (quote! {
+                #singular_resolver
                 #plural_resolver
+                #connection_resolver
+                #aggregate_resolvers
             }, query_struct)
         })
         .unzip();
 
         let gen = quote! {
-            use botanist::internal::{__internal__Preloadable, __internal__RootResolver};
+            use botanist::internal::{__internal__DefaultQueryModifier, __internal__Filterable, __internal__Keyable, __internal__Orderable, __internal__Preloadable, __internal__RootResolver};
 
             #( #query_types )*
 
@@ -189,14 +282,46 @@ pub fn botanist_query(attrs: TokenStream, input: TokenStream) -> TokenStream {
     panic!("Attempted to implement botanist_query on invalid query type!");
 }
 
-pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
+pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident> + Clone>(
     model: &Ident,
     schema: &Ident,
     graphql_type: &Ident,
     context: &Ident,
-    id_type: &Type,
+    primary_key: &common::PrimaryKey,
     searchable_fields: S,
 ) -> proc_macro2::TokenStream {
+    let id_type = primary_key.ty();
+    let id_matches = primary_key.matches(schema, quote! { id });
+    let ids_match = primary_key.matches_any(schema, context, quote! { ids });
+    let key_order = primary_key.order(schema, false);
+    let connection_type = common::connection_struct(model);
+    let order_by_type = common::order_by_struct(model);
+    let filter_type = common::filter_struct(model);
+
+    // Connections are always ordered by their primary key, so they only take on the search filters
+    let connection_search_filters = searchable_fields.clone().map(|field| {
+        let field_str = field.to_string();
+
+        if cfg!(feature = "postgres_prefix_search") {
+            quote! {
+                if let Some(search_query) = search_query.get(#field_str) {
+                    query = query.or_filter(
+                        prefix_search::matches(
+                            prefix_search::to_tsvector(#schema::#field),
+                            prefix_search::to_tsquery(format!("{}:*", search_query))
+                        )
+                    );
+                }
+            }
+        } else {
+            quote! {
+                if let Some(search_query) = search_query.get(#field_str) {
+                    query = query.or_filter(#schema::#field.ilike(format!("%{}%", search_query)));
+                }
+            }
+        }
+    }).collect::<Vec<proc_macro2::TokenStream>>();
+
     // Generate a case for extending the query with every eligible field that can support text searching
     // These query extensions only apply if the user specifies the field in question is searchable
     let search_filters = searchable_fields.map(|field| {
@@ -230,11 +355,15 @@ pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
     });
 
     quote! {
-        impl __internal__RootResolver<#context, #id_type, #graphql_type, juniper::DefaultScalarValue> for #model {
-            fn resolve_single(context: &Context, id: #id_type) -> juniper::FieldResult<#graphql_type> {
-                    match #model::modify_query(
+        impl __internal__RootResolver<#context, #id_type, #graphql_type, #connection_type, #order_by_type, #filter_type, juniper::DefaultScalarValue> for #model {
+            fn resolve_single(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                id: #id_type
+            ) -> juniper::FieldResult<#graphql_type> {
+                    match <#model as __internal__DefaultQueryModifier<_, #context>>::modify_query(
                         #schema::table
-                            .filter(#schema::id.eq(id))
+                            .filter(#id_matches)
                             .into_boxed(),
                         context
                     ) {
@@ -245,7 +374,7 @@ pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
                                 .get_result::<#model>(&connection)
                                 .map_or_else(
                                     |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
-                                    |model| Ok(#graphql_type::from(model.to_owned()))
+                                    |model| #graphql_type::preloaded(context, executor, model)
                                 )
                         },
                         Err(e) => Err(e)
@@ -259,10 +388,12 @@ pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
                 ids: Option<Vec<#id_type>>,
                 limit: Option<i32>,
                 offset: Option<i32>,
+                order_by: Option<Vec<#order_by_type>>,
+                where_filter: Option<#filter_type>,
                 search_query: Option<std::collections::HashMap<String, String>>
             ) -> juniper::FieldResult<Vec<#graphql_type>> {
                 let mut query = if let Some(ids) = ids {
-                    #schema::table.filter(#schema::id.eq_any(ids))
+                    #schema::table.filter(#ids_match)
                         .limit(limit.unwrap_or(10) as i64)
                         .offset(offset.unwrap_or(0) as i64)
                         .into_boxed()
@@ -273,15 +404,29 @@ pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
                         .into_boxed()
                 };
 
+                if let Some(order_by) = order_by {
+                    for expression in #graphql_type::order_by_expressions(&order_by) {
+                        query = query.then_order_by(expression);
+                    }
+                }
+
                 if let Some(search_query) = search_query {
                     #( #search_filters )*
                 }
 
-                match #model::modify_query(query, context) {
+                if let Some(where_filter) = where_filter {
+                    if let Some(expression) = where_filter.to_expression(context)? {
+                        query = query.filter(expression);
+                    }
+                }
+
+                match <#model as __internal__DefaultQueryModifier<_, #context>>::modify_query(query, context) {
                     Ok(query) => {
                         let connection = context.get_connection();
 
+                        // Ties are broken by primary key so pagination is deterministic
                         query
+                            .then_order_by(#key_order)
                             .load::<#model>(&connection)
                             .map_or_else(
                                 |error| Err(juniper::FieldError::new(error.to_string(), juniper::Value::null())),
@@ -290,7 +435,7 @@ pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
                                     let preload_result = #graphql_type::preload_children(&gql_models, &context, &executor.look_ahead());
 
                                     if let Err(preload_err) = preload_result {
-                                        Err(juniper::FieldError::new(preload_err.to_string(), juniper::Value::null()))
+                                        Err(preload_err)
                                     } else {
                                         Ok(gql_models)
                                     }
@@ -300,6 +445,46 @@ pub fn generate_root_resolvers<'a, S: Iterator<Item = &'a Ident>>(
                     Err(e) => Err(e)
                 }
             }
+
+            fn resolve_connection(
+                context: &#context,
+                executor: &juniper::Executor<#context, juniper::DefaultScalarValue>,
+                ids: Option<Vec<#id_type>>,
+                where_filter: Option<#filter_type>,
+                first: Option<i32>,
+                after: Option<String>,
+                last: Option<i32>,
+                before: Option<String>,
+                search_query: Option<std::collections::HashMap<String, String>>
+            ) -> juniper::FieldResult<#connection_type> {
+                #connection_type::load(
+                    context,
+                    &executor.look_ahead(),
+                    || {
+                        let mut query = if let Some(ids) = &ids {
+                            #schema::table.filter(#ids_match).into_boxed()
+                        } else {
+                            #schema::table.into_boxed()
+                        };
+
+                        if let Some(search_query) = &search_query {
+                            #( #connection_search_filters )*
+                        }
+
+                        if let Some(where_filter) = &where_filter {
+                            if let Some(expression) = where_filter.to_expression(context)? {
+                                query = query.filter(expression);
+                            }
+                        }
+
+                        <#model as __internal__DefaultQueryModifier<_, #context>>::modify_query(query, context)
+                    },
+                    first,
+                    after,
+                    last,
+                    before
+                )
+            }
         }
     }
 }
@@ -15,17 +15,17 @@ module.exports = {
             {
                 title: 'Getting Started',
                 collapsable: false,
-                children: [ 'intro/basics', 'intro/schema', 'intro/text_search' ]
+                children: [ 'intro/basics', 'intro/schema', 'intro/text_search', 'intro/filtering', 'intro/ordering' ]
             },
             {
                 title: 'Relationships',
                 collapsable: false,
-                children: [ 'relationships/has_one', 'relationships/has_many' ]
+                children: [ 'relationships/has_one', 'relationships/has_many', 'relationships/has_many_through' ]
             },
             {
                 title: 'Advanced',
                 collapsable: false,
-                children: [ 'advanced/query_modifier', 'advanced/query_options', 'advanced/preloading' ]
+                children: [ 'advanced/query_modifier', 'advanced/mutation_hooks', 'advanced/nested_mutations', 'advanced/relation_mutations', 'advanced/upserts', 'advanced/query_options', 'advanced/preloading', 'advanced/connections', 'advanced/aggregates', 'advanced/async' ]
             }
         ]
     },